@@ -0,0 +1,63 @@
+// Writes corpus seeds built from the woofi_jupiter::testing fixtures into fuzz/corpus/<target>/,
+// so the fuzzer starts from accounts that get through `update` and into swap_math.
+use std::{fs, path::PathBuf};
+
+use solana_sdk::sysvar;
+use woofi_jupiter::testing::MarketFixture;
+use woofi_jupiter_fuzz::encode_market;
+
+// (amount, selector) pairs as laid out in woofi_jupiter_fuzz
+const REQUESTS: [(u64, u8); 6] = [
+    (1_000_000_000, 0b0100),
+    (150_000_000, 0b0001),
+    (150_000_000, 0b1_0100),
+    (1_000_000_000, 0b1_0001),
+    (u64::MAX, 0b0100),
+    (1, 0b0001),
+];
+
+fn main() -> std::io::Result<()> {
+    let corpus = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    let deserialize_dir = corpus.join("deserialize_accounts");
+    let quote_dir = corpus.join("quote");
+    fs::create_dir_all(&deserialize_dir)?;
+    fs::create_dir_all(&quote_dir)?;
+
+    let mut paused = MarketFixture::sol_usdc();
+    paused.wooconfig.paused = true;
+    let mut stale = MarketFixture::sol_usdc();
+    stale.advance_clock(3_600);
+
+    let markets = [
+        ("sol_usdc", MarketFixture::sol_usdc()),
+        ("sol_woo", MarketFixture::sol_woo()),
+        ("sol_usdc_paused", paused),
+        ("sol_usdc_stale", stale),
+    ];
+
+    for (name, fixture) in &markets {
+        let keyed_account = fixture.keyed_account();
+        let account_map = fixture.account_map();
+
+        fs::write(
+            quote_dir.join(name),
+            encode_market(&keyed_account, &account_map, &REQUESTS),
+        )?;
+
+        fs::write(
+            deserialize_dir.join(format!("{}_pool", name)),
+            &keyed_account.account.data,
+        )?;
+        for (key, account) in account_map
+            .iter()
+            .filter(|(key, _)| **key != sysvar::clock::ID)
+        {
+            fs::write(
+                deserialize_dir.join(format!("{}_{}", name, key)),
+                &account.data,
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use anchor_lang::AccountDeserialize;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
+use solana_sdk::{account::Account, clock::Clock, pubkey::Pubkey};
+use woofi_jupiter::{
+    state::{WooAmmPool, WooConfig, WooPool, Wooracle},
+    testing::{FIXTURE_SLOT, FIXTURE_UNIX_TIMESTAMP},
+    WoofiSwap,
+};
+
+// Input layout of the `quote` target, written by `encode_market`:
+//   [u32 LE length][WooAmmPool data]
+//   [u32 LE length][account data] for each key of `get_accounts_to_update`, in order
+//   then 9 bytes per quote request: amount (u64 LE) and a selector byte, where
+//   bits 0-1 pick the input mint, bits 2-3 the output mint (0 = token a, 1 = token b,
+//   otherwise an unknown mint) and bit 4 selects ExactOut.
+// Truncated input reads as empty accounts, so every byte string is a valid case.
+
+pub const QUOTE_REQUEST_LEN: usize = 9;
+
+pub fn deserialize_accounts(data: &[u8]) {
+    let _ = WooAmmPool::try_deserialize(&mut &data[..]);
+    let _ = WooPool::try_deserialize(&mut &data[..]);
+    let _ = Wooracle::try_deserialize(&mut &data[..]);
+    let _ = WooConfig::try_deserialize(&mut &data[..]);
+}
+
+pub fn quote(data: &[u8]) {
+    let mut reader = Reader { data };
+
+    let keyed_account = KeyedAccount {
+        key: Pubkey::default(),
+        account: program_account(reader.section()),
+        params: None,
+    };
+    let Ok(mut woofi_swap) = WoofiSwap::from_keyed_account(&keyed_account, &amm_context()) else {
+        return;
+    };
+
+    let mut account_map = AccountMap::default();
+    for key in woofi_swap.get_accounts_to_update() {
+        account_map.insert(key, program_account(reader.section()));
+    }
+    if woofi_swap.update(&account_map).is_err() {
+        return;
+    }
+
+    let mints = woofi_swap.get_reserve_mints();
+    let mint = |index: u8| mints.get(index as usize).copied().unwrap_or_default();
+    while let Some(request) = reader.take(QUOTE_REQUEST_LEN) {
+        let amount = u64::from_le_bytes(request[..8].try_into().unwrap());
+        let selector = request[8];
+        let _ = woofi_swap.quote(&QuoteParams {
+            amount,
+            input_mint: mint(selector & 0b11),
+            output_mint: mint((selector >> 2) & 0b11),
+            swap_mode: if selector & 0b1_0000 == 0 {
+                SwapMode::ExactIn
+            } else {
+                SwapMode::ExactOut
+            },
+        });
+    }
+}
+
+// Seed for the `quote` target from a market the fixture builders produced
+pub fn encode_market(
+    keyed_account: &KeyedAccount,
+    account_map: &AccountMap,
+    requests: &[(u64, u8)],
+) -> Vec<u8> {
+    let mut data = Vec::new();
+    push_section(&mut data, &keyed_account.account.data);
+
+    if let Ok(woofi_swap) = WoofiSwap::from_keyed_account(keyed_account, &amm_context()) {
+        for key in woofi_swap.get_accounts_to_update() {
+            let account_data = account_map
+                .get(&key)
+                .map(|account| account.data.as_slice())
+                .unwrap_or_default();
+            push_section(&mut data, account_data);
+        }
+    }
+
+    for (amount, selector) in requests {
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(*selector);
+    }
+    data
+}
+
+fn push_section(data: &mut Vec<u8>, section: &[u8]) {
+    data.extend_from_slice(&(section.len() as u32).to_le_bytes());
+    data.extend_from_slice(section);
+}
+
+fn program_account(data: &[u8]) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data: data.to_vec(),
+        owner: woofi_jupiter::id(),
+        executable: false,
+        rent_epoch: u64::MAX,
+    }
+}
+
+// the fixture clock, so seeded oracles are fresh until the clock account says otherwise
+fn amm_context() -> AmmContext {
+    AmmContext {
+        clock_ref: ClockRef::from(Clock {
+            slot: FIXTURE_SLOT,
+            unix_timestamp: FIXTURE_UNIX_TIMESTAMP,
+            ..Clock::default()
+        }),
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(head)
+    }
+
+    // a length-prefixed section, clamped to what is left
+    fn section(&mut self) -> &'a [u8] {
+        let Some(len) = self.take(4) else {
+            return &[];
+        };
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        let len = len.min(self.data.len());
+        self.take(len).unwrap_or_default()
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::util::{get_wooconfig_address, SOL, USDC};
+
+pub const DEVNET_PROGRAM_ID: Pubkey = pubkey!("Es677W33uwrXLSqjV3rqcz5sftyarupdV3vDpQ9LXGow");
+pub const DEVNET_USDC: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cluster {
+    MainnetBeta,
+    Devnet,
+}
+
+impl Cluster {
+    pub fn deployment(self) -> WoofiDeployment {
+        match self {
+            Cluster::MainnetBeta => WoofiDeployment::new(self, crate::ID, USDC),
+            Cluster::Devnet => WoofiDeployment::new(self, DEVNET_PROGRAM_ID, DEVNET_USDC),
+        }
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cluster::MainnetBeta => write!(f, "mainnet-beta"),
+            Cluster::Devnet => write!(f, "devnet"),
+        }
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet-beta" | "mainnet" => Ok(Cluster::MainnetBeta),
+            "devnet" => Ok(Cluster::Devnet),
+            other => Err(anyhow!("Unknown cluster {}", other)),
+        }
+    }
+}
+
+// Where WooFi lives on one cluster
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WoofiDeployment {
+    pub cluster: Cluster,
+    pub program_id: Pubkey,
+    pub wooconfig: Pubkey,
+    // the quote token every pool prices against
+    pub usdc_mint: Pubkey,
+    pub sol_mint: Pubkey,
+}
+
+impl WoofiDeployment {
+    fn new(cluster: Cluster, program_id: Pubkey, usdc_mint: Pubkey) -> Self {
+        WoofiDeployment {
+            cluster,
+            program_id,
+            wooconfig: get_wooconfig_address(&program_id).0,
+            usdc_mint,
+            sol_mint: SOL,
+        }
+    }
+
+    pub fn mainnet() -> Self {
+        Cluster::MainnetBeta.deployment()
+    }
+
+    pub fn devnet() -> Self {
+        Cluster::Devnet.deployment()
+    }
+
+    pub fn all() -> [WoofiDeployment; 2] {
+        [Self::mainnet(), Self::devnet()]
+    }
+
+    // The deployment a WooFi account belongs to, from the program that owns it
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|deployment| deployment.program_id == *program_id)
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{errors::QuoteError, limits::SwapLimit, util::checked_to_u64, WoofiSwap};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthPoint {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    // output tokens per input token
+    pub execution_price: Decimal,
+    pub price_impact_bps: Decimal,
+}
+
+// ExactIn quotes of increasing size in one direction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthLadder {
+    pub input_mint: String,
+    pub output_mint: String,
+    // zero-size price the impact of every point is measured against, None if no point was quoted
+    pub marginal_price: Option<Decimal>,
+    pub points: Vec<DepthPoint>,
+    // constraint the first size that could not be quoted ran into, None if every size was quoted
+    pub limit: Option<SwapLimit>,
+}
+
+impl DepthLadder {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+// `count` sizes starting at `first`, each `ratio` times the previous, stopping before u64 overflows
+pub fn geometric_sizes(first: u64, ratio: u64, count: usize) -> Vec<u64> {
+    std::iter::successors(Some(first), |size| size.checked_mul(ratio))
+        .take(count)
+        .collect()
+}
+
+// `count` evenly spaced sizes: step, 2 * step, ...
+pub fn linear_sizes(step: u64, count: usize) -> Vec<u64> {
+    (1..=count as u64)
+        .map_while(|i| step.checked_mul(i))
+        .collect()
+}
+
+impl WoofiSwap {
+    // Quotes `sizes` (native units of the input token) in increasing order until one runs into a size limit.
+    // Sizes below the minimum swap amount are skipped; any other error is returned.
+    pub fn depth_ladder(
+        &self,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        sizes: &[u64],
+    ) -> Result<DepthLadder> {
+        let mut sizes = sizes.to_vec();
+        sizes.sort_unstable();
+        sizes.dedup();
+
+        let mut ladder = DepthLadder {
+            input_mint: input_mint.to_string(),
+            output_mint: output_mint.to_string(),
+            marginal_price: None,
+            points: vec![],
+            limit: None,
+        };
+
+        for amount in sizes {
+            let swap_result = match self.compute_swap(amount as u128, input_mint, output_mint) {
+                Ok(swap_result) => swap_result,
+                Err(error) => {
+                    if let Some(limit) = SwapLimit::from_error(&error) {
+                        ladder.limit = Some(limit);
+                        break;
+                    }
+                    if matches!(
+                        error.downcast_ref::<QuoteError>(),
+                        Some(QuoteError::BelowMinSwapAmount { .. })
+                    ) {
+                        continue;
+                    }
+                    return Err(error);
+                }
+            };
+            let price_impact = self.swap_price_impact(&swap_result, input_mint, output_mint)?;
+
+            ladder.marginal_price = Some(price_impact.marginal_price);
+            ladder.points.push(DepthPoint {
+                in_amount: amount,
+                out_amount: checked_to_u64(swap_result.out_amount)?,
+                execution_price: price_impact.execution_price,
+                price_impact_bps: price_impact.price_impact_bps,
+            });
+        }
+
+        Ok(ladder)
+    }
+
+    // Ladders for token a -> token b and token b -> token a, each with sizes in its own input token
+    pub fn depth_ladders(&self, sizes_a: &[u64], sizes_b: &[u64]) -> Result<[DepthLadder; 2]> {
+        Ok([
+            self.depth_ladder(&self.token_a_mint, &self.token_b_mint, sizes_a)?,
+            self.depth_ladder(&self.token_b_mint, &self.token_a_mint, sizes_b)?,
+        ])
+    }
+}
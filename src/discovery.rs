@@ -0,0 +1,180 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use jupiter_amm_interface::KeyedAccount;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::{
+    cluster::WoofiDeployment,
+    errors::DiscoveryError,
+    params::PARAM_PROGRAM_ID,
+    state::WooAmmPool,
+    util::{get_wooammpool_address, get_wooconfig_address},
+};
+
+// A WooAmmPool found among the program's accounts, checked against its PDA
+#[derive(Clone, Debug)]
+pub struct DiscoveredPool {
+    pub address: Pubkey,
+    pub account: Account,
+    pub pool: WooAmmPool,
+}
+
+impl DiscoveredPool {
+    // Params `from_keyed_account` needs for this pool: a programId for deployments it does not know
+    pub fn params(&self) -> Option<Value> {
+        match WoofiDeployment::from_program_id(&self.account.owner) {
+            Some(_) => None,
+            None => Some(json!({ PARAM_PROGRAM_ID: self.account.owner.to_string() })),
+        }
+    }
+
+    pub fn keyed_account(&self) -> KeyedAccount {
+        KeyedAccount {
+            key: self.address,
+            account: self.account.clone(),
+            params: self.params(),
+        }
+    }
+
+    pub fn market_cache_entry(&self) -> MarketCacheEntry {
+        MarketCacheEntry::new(&self.keyed_account())
+    }
+}
+
+// Result of scanning a program's accounts: the pools, and accounts that looked like pools but
+// were not accepted
+#[derive(Clone, Debug, Default)]
+pub struct Discovery {
+    pub pools: Vec<DiscoveredPool>,
+    pub rejected: Vec<(Pubkey, DiscoveryError)>,
+}
+
+impl Discovery {
+    pub fn keyed_accounts(&self) -> Vec<KeyedAccount> {
+        self.pools
+            .iter()
+            .map(DiscoveredPool::keyed_account)
+            .collect()
+    }
+
+    pub fn market_cache(&self) -> Vec<MarketCacheEntry> {
+        self.pools
+            .iter()
+            .map(DiscoveredPool::market_cache_entry)
+            .collect()
+    }
+
+    pub fn market_cache_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.market_cache())?)
+    }
+}
+
+// Finds the WooAmmPools in `accounts`, as returned by getProgramAccounts for `program_id`.
+// Other WooFi accounts (woopools, wooracles, wooconfig) are skipped; pools come out sorted by address.
+pub fn discover_pools(program_id: &Pubkey, accounts: &[(Pubkey, Account)]) -> Discovery {
+    let wooconfig = get_wooconfig_address(program_id).0;
+    let mut discovery = Discovery::default();
+
+    for (address, account) in accounts {
+        if account.data.get(..8) != Some(&WooAmmPool::DISCRIMINATOR[..]) {
+            continue;
+        }
+        match validate_pool(program_id, &wooconfig, address, account) {
+            Ok(pool) => discovery.pools.push(DiscoveredPool {
+                address: *address,
+                account: account.clone(),
+                pool,
+            }),
+            Err(error) => discovery.rejected.push((*address, error)),
+        }
+    }
+
+    discovery.pools.sort_by_key(|pool| pool.address);
+    discovery.pools.dedup_by_key(|pool| pool.address);
+    discovery
+}
+
+fn validate_pool(
+    program_id: &Pubkey,
+    wooconfig: &Pubkey,
+    address: &Pubkey,
+    account: &Account,
+) -> Result<WooAmmPool, DiscoveryError> {
+    if account.owner != *program_id {
+        return Err(DiscoveryError::WrongOwner {
+            owner: account.owner,
+        });
+    }
+
+    let pool = WooAmmPool::try_deserialize(&mut account.data.as_slice())
+        .map_err(|_| DiscoveryError::Undecodable)?;
+
+    if pool.wooconfig != *wooconfig {
+        return Err(DiscoveryError::WooconfigMismatch {
+            wooconfig: pool.wooconfig,
+            expected: *wooconfig,
+        });
+    }
+
+    let expected = get_wooammpool_address(
+        &pool.wooconfig,
+        &pool.token_mint_a,
+        &pool.token_mint_b,
+        program_id,
+    )
+    .0;
+    if expected != *address {
+        return Err(DiscoveryError::AddressMismatch { expected });
+    }
+
+    Ok(pool)
+}
+
+// One entry of a Jupiter market cache, accounts encoded as by getAccountInfo with base64 encoding
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketCacheEntry {
+    pub pubkey: String,
+    pub lamports: u64,
+    // [base64 data, "base64"]
+    pub data: (String, String),
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub space: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl MarketCacheEntry {
+    pub fn new(keyed_account: &KeyedAccount) -> Self {
+        let account = &keyed_account.account;
+        MarketCacheEntry {
+            pubkey: keyed_account.key.to_string(),
+            lamports: account.lamports,
+            data: (STANDARD.encode(&account.data), "base64".into()),
+            owner: account.owner.to_string(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            space: account.data.len(),
+            params: keyed_account.params.clone(),
+        }
+    }
+
+    pub fn keyed_account(&self) -> Result<KeyedAccount> {
+        Ok(KeyedAccount {
+            key: self.pubkey.parse()?,
+            account: Account {
+                lamports: self.lamports,
+                data: STANDARD.decode(&self.data.0)?,
+                owner: self.owner.parse()?,
+                executable: self.executable,
+                rent_epoch: self.rent_epoch,
+            },
+            params: self.params.clone(),
+        })
+    }
+}
@@ -1,5 +1,6 @@
 use std::num::TryFromIntError;
 
+use solana_sdk::pubkey::Pubkey;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -71,3 +72,98 @@ impl From<TryFromIntError> for ErrorCode {
         ErrorCode::NumberCastError
     }
 }
+
+// Why a quote was rejected. Returned through anyhow, so callers can
+// `downcast_ref::<QuoteError>()` to tell limits apart from math or decoding failures.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteError {
+    #[error("Woofi is paused")]
+    Paused,
+    #[error("Mint {mint} is not traded against the other side by this market")]
+    InvalidMint { mint: Pubkey },
+    // reason is None when no OracleHealth was recorded for the mint
+    #[error(
+        "Woo oracle for {mint} is not feasible{}",
+        .reason.map(|reason| format!(": {}", reason)).unwrap_or_default()
+    )]
+    OracleNotFeasible {
+        mint: Pubkey,
+        reason: Option<OracleFailure>,
+    },
+    #[error("Woo oracle price {price} outside range [{range_min}, {range_max}]")]
+    PriceOutOfRange {
+        price: u128,
+        range_min: u128,
+        range_max: u128,
+    },
+    #[error("Notional {notional} exceeds max notional swap {max_notional_swap}")]
+    MaxNotionalExceeded {
+        notional: u128,
+        max_notional_swap: u128,
+    },
+    #[error("Gamma {gamma} exceeds max gamma {max_gamma}")]
+    GammaExceeded { gamma: u128, max_gamma: u128 },
+    #[error("Balance {balance_after} after swap exceeds balance cap {cap_bal}")]
+    BalanceCapExceeded { balance_after: u128, cap_bal: u128 },
+    #[error("Gamma {gamma} plus spread {spread} reaches 1e18")]
+    SpreadPlusGammaExceeded { gamma: u128, spread: u64 },
+    // ExactOut amount past the most the pool pays out before gamma makes the output fall
+    #[error("Output {target} is above the most the swap pays out, {max_out}")]
+    OutputUnreachable { target: u128, max_out: u128 },
+    #[error("Swap needs {required} from the quote token vault but only {available} is available")]
+    InsufficientQuoteReserves { required: u128, available: u128 },
+    #[error("Swap needs {required} from reserves but only {available} is available")]
+    InsufficientReserves { required: u128, available: u128 },
+    #[error("Amount {amount} is not above min swap amount {min_swap_amount}")]
+    BelowMinSwapAmount { amount: u128, min_swap_amount: u128 },
+    #[error("Amount {amount} does not fit in a u64 token amount")]
+    AmountOverflow { amount: u128 },
+    #[error("Missing {0}, update has not run")]
+    Uninitialized(&'static str),
+}
+
+// The first OracleHealth check a wooracle fails, in the order the program applies them
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleFailure {
+    #[error("pyth price is unverified, stale, for another feed or not positive")]
+    PythUnusable,
+    #[error("pyth confidence interval is wider than the wooracle bound or maxConfRatio")]
+    ConfidenceTooWide,
+    #[error("wooracle price is older than stale_duration")]
+    Stale,
+    #[error("wooracle price is outside the bound around pyth")]
+    OutOfBound,
+    #[error("wooracle price is outside range_min/range_max")]
+    OutOfRange,
+}
+
+// Why `KeyedAccount.params` was rejected by `from_keyed_account`
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParamsError {
+    #[error("Market params must be a JSON object")]
+    NotAnObject,
+    #[error("Unknown market param {0}")]
+    UnknownKey(String),
+    #[error("Market param {key} is invalid: {reason}")]
+    InvalidValue { key: &'static str, reason: String },
+    #[error("Pool is owned by {owner}, not by the programId param {program_id}")]
+    ProgramIdMismatch { program_id: Pubkey, owner: Pubkey },
+    #[error("Pool quotes against {pool_quote_mint}, not the quoteMint param {quote_mint}")]
+    QuoteMintMismatch {
+        quote_mint: Pubkey,
+        pool_quote_mint: Pubkey,
+    },
+}
+
+// Why an account carrying the WooAmmPool discriminator was not accepted as a pool
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryError {
+    #[error("Account is owned by {owner}, not the WooFi program")]
+    WrongOwner { owner: Pubkey },
+    #[error("Account does not deserialize as a WooAmmPool")]
+    Undecodable,
+    #[error("Pool uses wooconfig {wooconfig}, expected {expected}")]
+    WooconfigMismatch { wooconfig: Pubkey, expected: Pubkey },
+    #[error("Pool is not at its PDA {expected}")]
+    AddressMismatch { expected: Pubkey },
+}
@@ -0,0 +1,225 @@
+use anchor_lang::prelude::{borsh, AnchorSerialize};
+use anyhow::Result;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    errors::ErrorCode,
+    state::WooAmmPool,
+    util::{get_wooconfig_address, get_woopool_address, get_wooracle_address},
+};
+
+// sha256("global:swap")[..8]
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+// sha256("global:try_query")[..8]
+pub const TRY_QUERY_DISCRIMINATOR: [u8; 8] = [132, 54, 250, 71, 244, 207, 193, 163];
+
+#[derive(AnchorSerialize, Clone, Copy, Debug)]
+pub struct SwapArgs {
+    pub from_amount: u128,
+    pub min_to_amount: u128,
+}
+
+#[derive(AnchorSerialize, Clone, Copy, Debug)]
+pub struct TryQueryArgs {
+    pub from_amount: u128,
+}
+
+// Pyth and vault accounts of a token listed on WooFi, enough to derive its wooracle and woopool
+#[derive(Clone, Copy, Debug)]
+pub struct TokenAccounts {
+    pub token_mint: Pubkey,
+    pub feed_account: Pubkey,
+    pub price_update: Pubkey,
+    pub token_vault: Pubkey,
+}
+
+// Program owned accounts shared by `swap` and `try_query`, oriented from the input token
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapPoolAccounts {
+    pub wooconfig: Pubkey,
+    pub wooracle_from: Pubkey,
+    pub woopool_from: Pubkey,
+    pub token_vault_from: Pubkey,
+    pub price_update_from: Pubkey,
+    pub wooracle_to: Pubkey,
+    pub woopool_to: Pubkey,
+    pub token_vault_to: Pubkey,
+    pub price_update_to: Pubkey,
+    pub woopool_quote: Pubkey,
+    pub quote_price_update: Pubkey,
+    pub quote_token_vault: Pubkey,
+}
+
+// Accounts supplied by the swapper
+#[derive(Clone, Copy, Debug)]
+pub struct SwapUserAccounts {
+    pub payer: Pubkey,
+    pub token_owner_account_from: Pubkey,
+    pub token_owner_account_to: Pubkey,
+    pub rebate_to: Pubkey,
+}
+
+impl SwapPoolAccounts {
+    pub fn from_woo_amm_pool(
+        pool: &WooAmmPool,
+        from_mint: &Pubkey,
+        to_mint: &Pubkey,
+    ) -> Result<Self> {
+        let side_a = (
+            pool.wooracle_a,
+            pool.woopool_a,
+            pool.token_vault_a,
+            pool.price_update_a,
+        );
+        let side_b = (
+            pool.wooracle_b,
+            pool.woopool_b,
+            pool.token_vault_b,
+            pool.price_update_b,
+        );
+
+        let (from, to) = if *from_mint == pool.token_mint_a && *to_mint == pool.token_mint_b {
+            (side_a, side_b)
+        } else if *from_mint == pool.token_mint_b && *to_mint == pool.token_mint_a {
+            (side_b, side_a)
+        } else {
+            return Err(ErrorCode::SwapPoolInvalid.into());
+        };
+
+        Ok(SwapPoolAccounts {
+            wooconfig: pool.wooconfig,
+            wooracle_from: from.0,
+            woopool_from: from.1,
+            token_vault_from: from.2,
+            price_update_from: from.3,
+            wooracle_to: to.0,
+            woopool_to: to.1,
+            token_vault_to: to.2,
+            price_update_to: to.3,
+            woopool_quote: pool.quote_woopool,
+            quote_price_update: pool.quote_price_update,
+            quote_token_vault: pool.quote_vault,
+        })
+    }
+
+    // Derives wooconfig, wooracles and woopools through the program PDAs
+    pub fn from_token_accounts(
+        program_id: &Pubkey,
+        from: &TokenAccounts,
+        to: &TokenAccounts,
+        quote: &TokenAccounts,
+    ) -> Self {
+        let wooconfig = get_wooconfig_address(program_id).0;
+        let wooracle = |token: &TokenAccounts| {
+            get_wooracle_address(
+                &wooconfig,
+                &token.token_mint,
+                &token.feed_account,
+                &token.price_update,
+                program_id,
+            )
+            .0
+        };
+        let woopool = |token: &TokenAccounts| {
+            get_woopool_address(&wooconfig, &token.token_mint, &quote.token_mint, program_id).0
+        };
+
+        SwapPoolAccounts {
+            wooconfig,
+            wooracle_from: wooracle(from),
+            woopool_from: woopool(from),
+            token_vault_from: from.token_vault,
+            price_update_from: from.price_update,
+            wooracle_to: wooracle(to),
+            woopool_to: woopool(to),
+            token_vault_to: to.token_vault,
+            price_update_to: to.price_update,
+            woopool_quote: woopool(quote),
+            quote_price_update: quote.price_update,
+            quote_token_vault: quote.token_vault,
+        }
+    }
+}
+
+// Account layout of the program's `swap` instruction
+pub fn swap_account_metas(
+    pool_accounts: &SwapPoolAccounts,
+    user_accounts: &SwapUserAccounts,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(pool_accounts.wooconfig, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(user_accounts.payer, true),
+        AccountMeta::new(pool_accounts.wooracle_from, false),
+        AccountMeta::new(pool_accounts.woopool_from, false),
+        AccountMeta::new(user_accounts.token_owner_account_from, false),
+        AccountMeta::new(pool_accounts.token_vault_from, false),
+        AccountMeta::new_readonly(pool_accounts.price_update_from, false),
+        AccountMeta::new(pool_accounts.wooracle_to, false),
+        AccountMeta::new(pool_accounts.woopool_to, false),
+        AccountMeta::new(user_accounts.token_owner_account_to, false),
+        AccountMeta::new(pool_accounts.token_vault_to, false),
+        AccountMeta::new_readonly(pool_accounts.price_update_to, false),
+        AccountMeta::new(pool_accounts.woopool_quote, false),
+        AccountMeta::new_readonly(pool_accounts.quote_price_update, false),
+        AccountMeta::new(pool_accounts.quote_token_vault, false),
+        AccountMeta::new_readonly(user_accounts.rebate_to, false),
+    ]
+}
+
+// Account layout of the program's `try_query` instruction
+pub fn try_query_account_metas(pool_accounts: &SwapPoolAccounts) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(pool_accounts.wooconfig, false),
+        AccountMeta::new_readonly(pool_accounts.wooracle_from, false),
+        AccountMeta::new_readonly(pool_accounts.woopool_from, false),
+        AccountMeta::new_readonly(pool_accounts.token_vault_from, false),
+        AccountMeta::new_readonly(pool_accounts.price_update_from, false),
+        AccountMeta::new_readonly(pool_accounts.wooracle_to, false),
+        AccountMeta::new_readonly(pool_accounts.woopool_to, false),
+        AccountMeta::new_readonly(pool_accounts.token_vault_to, false),
+        AccountMeta::new_readonly(pool_accounts.price_update_to, false),
+        AccountMeta::new_readonly(pool_accounts.woopool_quote, false),
+        AccountMeta::new_readonly(pool_accounts.quote_price_update, false),
+        AccountMeta::new_readonly(pool_accounts.quote_token_vault, false),
+    ]
+}
+
+pub fn swap(
+    program_id: &Pubkey,
+    pool_accounts: &SwapPoolAccounts,
+    user_accounts: &SwapUserAccounts,
+    from_amount: u128,
+    min_to_amount: u128,
+) -> Result<Instruction> {
+    let mut data = SWAP_DISCRIMINATOR.to_vec();
+    SwapArgs {
+        from_amount,
+        min_to_amount,
+    }
+    .serialize(&mut data)?;
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: swap_account_metas(pool_accounts, user_accounts),
+        data,
+    })
+}
+
+pub fn try_query(
+    program_id: &Pubkey,
+    pool_accounts: &SwapPoolAccounts,
+    from_amount: u128,
+) -> Result<Instruction> {
+    let mut data = TRY_QUERY_DISCRIMINATOR.to_vec();
+    TryQueryArgs { from_amount }.serialize(&mut data)?;
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: try_query_account_metas(pool_accounts),
+        data,
+    })
+}
@@ -1,4 +1,4 @@
-use anchor_lang::{declare_id, prelude::AccountMeta, AccountDeserialize};
+use anchor_lang::{declare_id, AccountDeserialize};
 use anchor_spl::token::spl_token::state::Account;
 /*
 
@@ -32,32 +32,45 @@ use anchor_spl::token::spl_token::state::Account;
 * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 
+use cluster::{Cluster, WoofiDeployment};
 use constants::ONE_E5_U128;
-use errors::ErrorCode;
-use solana_sdk::{program_pack::Pack, pubkey::Pubkey, sysvar};
+use errors::{ErrorCode, ParamsError, QuoteError};
+use instructions::{SwapPoolAccounts, SwapUserAccounts};
+use params::WoofiParams;
+use solana_sdk::{clock::Clock, program_pack::Pack, pubkey::Pubkey, sysvar};
 use state::{WooAmmPool, WooConfig, WooPool, Wooracle};
 use std::cmp::max;
 use util::{
-    balance, checked_mul_div_round_up, get_price, swap_math, Decimals, GetStateResult
+    balance, checked_decimal_mul, checked_mul_div_round_up, checked_to_u64, execution_price,
+    fee_rate_to_pct, get_price, marginal_price_sell_base, marginal_price_sell_quote, swap_math,
+    Decimals, GetStateResult, OracleGuard, OracleHealth, PriceImpact,
 };
 
 use jupiter_amm_interface::{
-    try_get_account_data, AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, SwapAndAccountMetas, SwapParams
+    try_get_account_data, AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams,
+    Swap, SwapAndAccountMetas, SwapMode, SwapParams,
 };
 
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use rust_decimal::Decimal;
 
+pub mod cluster;
 mod constants;
-mod errors;
+pub mod depth;
+pub mod discovery;
+pub mod errors;
+pub mod instructions;
+pub mod limits;
+pub mod params;
+pub mod simulation;
 pub mod state;
+#[cfg(feature = "test-utils")]
+pub mod testing;
 pub mod util;
 
-#[cfg(feature = "devnet")]
-declare_id!("Es677W33uwrXLSqjV3rqcz5sftyarupdV3vDpQ9LXGow");
-
-#[cfg(not(feature = "devnet"))]
+// mainnet; markets on other clusters take their program id from the pool account owner
 declare_id!("WooFif76YGRNjk1pA8wCsN67aQsD9f9iLsz4NcJ1AVb");
 
 #[derive(Clone)]
@@ -68,6 +81,8 @@ pub struct WoofiSwap {
     pub token_a_mint: Pubkey,
     pub token_b_mint: Pubkey,
     pub program_id: Pubkey,
+    // None for a deployment only known through the programId param
+    pub cluster: Option<Cluster>,
 
     pub wooconfig: Pubkey,
     pub token_a_wooracle: Pubkey,
@@ -84,6 +99,10 @@ pub struct WoofiSwap {
     pub usdc_price_update: Pubkey,
     pub usdc_woopool: Pubkey,
     pub usdc_vault: Pubkey,
+    // rebate account passed to the swap instruction, the swapper when not set
+    pub rebate_to: Option<Pubkey>,
+    pub oracle_guard_a: OracleGuard,
+    pub oracle_guard_b: OracleGuard,
 
     pub fee_rate: u16,
     pub wooconfig_state: Option<WooConfig>,
@@ -96,18 +115,519 @@ pub struct WoofiSwap {
     pub token_b_balance: Option<u128>,
     pub woopool_b: Option<WooPool>,
     pub usdc_balance: Option<u128>,
+    pub woopool_quote: Option<WooPool>,
+    pub oracle_health_a: Option<OracleHealth>,
+    pub oracle_health_b: Option<OracleHealth>,
     pub clock_ref: ClockRef,
 }
 
+// Outcome of one swap against the cached state
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    pub in_amount: u128,
+    pub out_amount: u128,
+    // quote token amount before the swap fee
+    pub usdc_amount: u128,
+    pub swap_fee: u128,
+    // wooracle prices after the swap, None on the quote token side
+    pub new_price_from: Option<u128>,
+    pub new_price_to: Option<u128>,
+}
+
+struct SwapLegs<'a> {
+    decimals_from: &'a Decimals,
+    state_from: &'a GetStateResult,
+    woopool_from: &'a WooPool,
+    decimals_to: &'a Decimals,
+    state_to: &'a GetStateResult,
+    woopool_to: &'a WooPool,
+    woopool_quote: &'a WooPool,
+    token_from_balance: u128,
+    token_to_balance: u128,
+    usdc_balance: u128,
+}
+
+impl WoofiSwap {
+    fn swap_legs(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<SwapLegs<'_>> {
+        let wooconfig = self
+            .wooconfig_state
+            .as_ref()
+            .ok_or(QuoteError::Uninitialized("wooconfig"))?;
+        if wooconfig.paused {
+            return Err(QuoteError::Paused.into());
+        }
+
+        // a to b or b to a; an input equal to the output reports the output mint
+        let sell_a = if *input_mint == self.token_a_mint && *output_mint == self.token_b_mint {
+            true
+        } else if *input_mint == self.token_b_mint && *output_mint == self.token_a_mint {
+            false
+        } else if *input_mint != self.token_a_mint && *input_mint != self.token_b_mint {
+            return Err(QuoteError::InvalidMint { mint: *input_mint }.into());
+        } else {
+            return Err(QuoteError::InvalidMint { mint: *output_mint }.into());
+        };
+
+        if sell_a {
+            Ok(SwapLegs {
+                decimals_from: self
+                    .decimals_a
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("decimals_a"))?,
+                state_from: self
+                    .state_a
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("state_a"))?,
+                woopool_from: self
+                    .woopool_a
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("woopool_a"))?,
+                decimals_to: self
+                    .decimals_b
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("decimals_b"))?,
+                state_to: self
+                    .state_b
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("state_b"))?,
+                woopool_to: self
+                    .woopool_b
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("woopool_b"))?,
+                token_from_balance: self
+                    .token_a_balance
+                    .ok_or(QuoteError::Uninitialized("token_a_balance"))?,
+                token_to_balance: self
+                    .token_b_balance
+                    .ok_or(QuoteError::Uninitialized("token_b_balance"))?,
+                woopool_quote: self
+                    .woopool_quote
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("woopool_quote"))?,
+                usdc_balance: self
+                    .usdc_balance
+                    .ok_or(QuoteError::Uninitialized("usdc_balance"))?,
+            })
+        } else {
+            Ok(SwapLegs {
+                decimals_from: self
+                    .decimals_b
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("decimals_b"))?,
+                state_from: self
+                    .state_b
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("state_b"))?,
+                woopool_from: self
+                    .woopool_b
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("woopool_b"))?,
+                decimals_to: self
+                    .decimals_a
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("decimals_a"))?,
+                state_to: self
+                    .state_a
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("state_a"))?,
+                woopool_to: self
+                    .woopool_a
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("woopool_a"))?,
+                token_from_balance: self
+                    .token_b_balance
+                    .ok_or(QuoteError::Uninitialized("token_b_balance"))?,
+                token_to_balance: self
+                    .token_a_balance
+                    .ok_or(QuoteError::Uninitialized("token_a_balance"))?,
+                woopool_quote: self
+                    .woopool_quote
+                    .as_ref()
+                    .ok_or(QuoteError::Uninitialized("woopool_quote"))?,
+                usdc_balance: self
+                    .usdc_balance
+                    .ok_or(QuoteError::Uninitialized("usdc_balance"))?,
+            })
+        }
+    }
+
+    // Fills in the OracleHealth reason of an OracleNotFeasible raised by the swap math
+    fn with_oracle_reason(&self, error: anyhow::Error) -> anyhow::Error {
+        match error.downcast_ref::<QuoteError>() {
+            Some(&QuoteError::OracleNotFeasible { mint, reason: None }) => {
+                let health = if mint == self.token_a_mint {
+                    self.oracle_health_a
+                } else if mint == self.token_b_mint {
+                    self.oracle_health_b
+                } else {
+                    None
+                };
+                QuoteError::OracleNotFeasible {
+                    mint,
+                    reason: health.and_then(|health| health.infeasible_reason()),
+                }
+                .into()
+            }
+            _ => error,
+        }
+    }
+
+    // Oracle inputs and feasibility for token a and token b as of the last update
+    pub fn oracle_health(&self) -> Result<[(Pubkey, OracleHealth); 2]> {
+        Ok([
+            (
+                self.token_a_mint,
+                self.oracle_health_a
+                    .ok_or(QuoteError::Uninitialized("oracle_health_a"))?,
+            ),
+            (
+                self.token_b_mint,
+                self.oracle_health_b
+                    .ok_or(QuoteError::Uninitialized("oracle_health_b"))?,
+            ),
+        ])
+    }
+
+    pub fn swap_pool_accounts(
+        &self,
+        source_mint: &Pubkey,
+        destination_mint: &Pubkey,
+    ) -> Result<SwapPoolAccounts> {
+        let side_a = (
+            self.token_a_wooracle,
+            self.token_a_woopool,
+            self.token_a_vault,
+            self.token_a_price_update,
+        );
+        let side_b = (
+            self.token_b_wooracle,
+            self.token_b_woopool,
+            self.token_b_vault,
+            self.token_b_price_update,
+        );
+
+        let (from, to) =
+            if *source_mint == self.token_a_mint && *destination_mint == self.token_b_mint {
+                (side_a, side_b)
+            } else if *source_mint == self.token_b_mint && *destination_mint == self.token_a_mint {
+                (side_b, side_a)
+            } else {
+                return Err(ErrorCode::SwapPoolInvalid.into());
+            };
+
+        Ok(SwapPoolAccounts {
+            wooconfig: self.wooconfig,
+            wooracle_from: from.0,
+            woopool_from: from.1,
+            token_vault_from: from.2,
+            price_update_from: from.3,
+            wooracle_to: to.0,
+            woopool_to: to.1,
+            token_vault_to: to.2,
+            price_update_to: to.3,
+            woopool_quote: self.usdc_woopool,
+            quote_price_update: self.usdc_price_update,
+            quote_token_vault: self.usdc_vault,
+        })
+    }
+
+    // Runs an ExactIn swap against the cached state, mirroring the program's swap instruction
+    pub fn compute_swap(
+        &self,
+        in_amount: u128,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+    ) -> Result<SwapResult> {
+        let SwapLegs {
+            decimals_from,
+            state_from,
+            woopool_from,
+            decimals_to,
+            state_to,
+            woopool_to,
+            woopool_quote,
+            token_from_balance,
+            token_to_balance,
+            usdc_balance,
+        } = self.swap_legs(input_mint, output_mint)?;
+
+        if in_amount <= woopool_from.min_swap_amount {
+            return Err(QuoteError::BelowMinSwapAmount {
+                amount: in_amount,
+                min_swap_amount: woopool_from.min_swap_amount,
+            }
+            .into());
+        }
+
+        let woopool_from_after = token_from_balance
+            .checked_add(in_amount)
+            .context("computation overflow")?;
+        if woopool_from_after > woopool_from.cap_bal {
+            return Err(QuoteError::BalanceCapExceeded {
+                balance_after: woopool_from_after,
+                cap_bal: woopool_from.cap_bal,
+            }
+            .into());
+        }
+
+        let mut new_price_from: Option<u128> = None;
+        let mut new_price_to: Option<u128> = None;
+
+        let usdc_amount: u128 = if *input_mint == self.usdc_mint {
+            in_amount
+        } else {
+            let (_usdc_amount, _new_price) = swap_math::calc_quote_amount_sell_base(
+                in_amount,
+                woopool_from,
+                decimals_from,
+                state_from,
+            )
+            .map_err(|error| self.with_oracle_reason(error))?;
+
+            new_price_from = Some(_new_price);
+            _usdc_amount
+        };
+
+        // base to base also passes through the quote token pool, which has its own minimum
+        if *input_mint != self.usdc_mint
+            && *output_mint != self.usdc_mint
+            && usdc_amount <= woopool_quote.min_swap_amount
+        {
+            return Err(QuoteError::BelowMinSwapAmount {
+                amount: usdc_amount,
+                min_swap_amount: woopool_quote.min_swap_amount,
+            }
+            .into());
+        }
+
+        let swap_fee = checked_mul_div_round_up(usdc_amount, self.fee_rate as u128, ONE_E5_U128)?;
+        let usdc_amount_after_fee = usdc_amount
+            .checked_sub(swap_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // sell base pays out usdc_amount, sell quote and base to base only keep the swap fee
+        let check_usdc_amount = if woopool_to.token_mint == self.usdc_mint {
+            usdc_amount
+        } else {
+            swap_fee
+        };
+
+        if usdc_balance < check_usdc_amount {
+            return Err(QuoteError::InsufficientQuoteReserves {
+                required: check_usdc_amount,
+                available: usdc_balance,
+            }
+            .into());
+        }
+
+        let to_amount: u128 = if *output_mint == self.usdc_mint {
+            usdc_amount_after_fee
+        } else {
+            let (_to_amount, _new_price) = swap_math::calc_base_amount_sell_quote(
+                usdc_amount_after_fee,
+                woopool_to,
+                decimals_to,
+                state_to,
+            )
+            .map_err(|error| self.with_oracle_reason(error))?;
+            new_price_to = Some(_new_price);
+            _to_amount
+        };
+
+        if token_to_balance < to_amount {
+            return Err(QuoteError::InsufficientReserves {
+                required: to_amount,
+                available: token_to_balance,
+            }
+            .into());
+        }
+
+        Ok(SwapResult {
+            in_amount,
+            out_amount: to_amount,
+            usdc_amount,
+            swap_fee,
+            new_price_from,
+            new_price_to,
+        })
+    }
+
+    fn quote_exact_in(
+        &self,
+        in_amount: u128,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+    ) -> Result<Quote> {
+        let swap_result = self.compute_swap(in_amount, input_mint, output_mint)?;
+        self.swap_result_quote(&swap_result)
+    }
+
+    fn swap_result_quote(&self, swap_result: &SwapResult) -> Result<Quote> {
+        Ok(Quote {
+            fee_pct: fee_rate_to_pct(self.fee_rate),
+            in_amount: checked_to_u64(swap_result.in_amount)?,
+            out_amount: checked_to_u64(swap_result.out_amount)?,
+            fee_amount: checked_to_u64(swap_result.swap_fee)?,
+            fee_mint: self.usdc_mint,
+            ..Quote::default()
+        })
+    }
+
+    // Input amount of an ExactIn or ExactOut request
+    fn quote_in_amount(&self, quote_params: &QuoteParams) -> Result<u128> {
+        match quote_params.swap_mode {
+            SwapMode::ExactIn => Ok(quote_params.amount as u128),
+            SwapMode::ExactOut => self.calc_in_amount_exact_out(
+                quote_params.amount as u128,
+                &quote_params.input_mint,
+                &quote_params.output_mint,
+            ),
+        }
+    }
+
+    // Zero-size, executed and post-trade prices of a quote, in output tokens per input token
+    pub fn price_impact(&self, quote_params: &QuoteParams) -> Result<PriceImpact> {
+        let input_mint = &quote_params.input_mint;
+        let output_mint = &quote_params.output_mint;
+        let swap_result =
+            self.compute_swap(self.quote_in_amount(quote_params)?, input_mint, output_mint)?;
+        self.swap_price_impact(&swap_result, input_mint, output_mint)
+    }
+
+    // `price_impact` of a swap already computed by `compute_swap` for these mints
+    pub fn swap_price_impact(
+        &self,
+        swap_result: &SwapResult,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+    ) -> Result<PriceImpact> {
+        let legs = self.swap_legs(input_mint, output_mint)?;
+
+        let fee_factor = Decimal::ONE - fee_rate_to_pct(self.fee_rate);
+        let marginal_price = |price_from: u128, price_to: u128| -> Result<Decimal> {
+            let from = if *input_mint == self.usdc_mint {
+                Decimal::ONE
+            } else {
+                marginal_price_sell_base(price_from, legs.state_from.spread, legs.decimals_from)?
+            };
+            let to = if *output_mint == self.usdc_mint {
+                Decimal::ONE
+            } else {
+                marginal_price_sell_quote(price_to, legs.state_to.spread, legs.decimals_to)?
+            };
+            checked_decimal_mul(checked_decimal_mul(from, to)?, fee_factor)
+        };
+
+        PriceImpact::new(
+            marginal_price(legs.state_from.price_out, legs.state_to.price_out)?,
+            execution_price(
+                swap_result.in_amount,
+                legs.decimals_from,
+                swap_result.out_amount,
+                legs.decimals_to,
+            )?,
+            marginal_price(
+                swap_result
+                    .new_price_from
+                    .unwrap_or(legs.state_from.price_out),
+                swap_result.new_price_to.unwrap_or(legs.state_to.price_out),
+            )?,
+        )
+    }
+
+    // Walks the ExactIn path backwards: the quote amount the output leg needs, grossed up
+    // for the swap fee, then the input the first leg needs to produce it.
+    fn calc_in_amount_exact_out(
+        &self,
+        out_amount: u128,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+    ) -> Result<u128> {
+        let legs = self.swap_legs(input_mint, output_mint)?;
+
+        let usdc_amount_after_fee: u128 = if *output_mint == self.usdc_mint {
+            out_amount
+        } else {
+            swap_math::calc_quote_amount_buy_base(
+                out_amount,
+                legs.woopool_to,
+                legs.decimals_to,
+                legs.state_to,
+            )
+            .map_err(|error| self.with_oracle_reason(error))?
+        };
+
+        // swap_fee rounds up, so usdc_amount - swap_fee == floor(usdc_amount * (1e5 - fee_rate) / 1e5)
+        let usdc_amount = checked_mul_div_round_up(
+            usdc_amount_after_fee,
+            ONE_E5_U128,
+            ONE_E5_U128 - self.fee_rate as u128,
+        )?;
+
+        if *input_mint == self.usdc_mint {
+            Ok(usdc_amount)
+        } else {
+            swap_math::calc_base_amount_buy_quote(
+                usdc_amount,
+                legs.woopool_from,
+                legs.decimals_from,
+                legs.state_from,
+            )
+            .map_err(|error| self.with_oracle_reason(error))
+        }
+    }
+}
+
 impl Amm for WoofiSwap {
     fn program_id(&self) -> Pubkey {
         self.program_id
     }
 
     fn from_keyed_account(keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<Self> {
-        let program_id = id();
+        let params = WoofiParams::parse(keyed_account.params.as_ref())?;
+
+        // a programId param vouches for deployments that are not in WoofiDeployment::all
+        let program_id = keyed_account.account.owner;
+        let cluster = match params.program_id {
+            Some(param_program_id) if param_program_id != program_id => {
+                return Err(ParamsError::ProgramIdMismatch {
+                    program_id: param_program_id,
+                    owner: program_id,
+                }
+                .into());
+            }
+            Some(_) => {
+                WoofiDeployment::from_program_id(&program_id).map(|deployment| deployment.cluster)
+            }
+            None => Some(
+                WoofiDeployment::from_program_id(&program_id)
+                    .with_context(|| {
+                        format!(
+                            "Pool {} is owned by {}, not a WooFi program",
+                            keyed_account.key, program_id
+                        )
+                    })?
+                    .cluster,
+            ),
+        };
 
-        let woo_amm_pool = &WooAmmPool::try_deserialize(&mut keyed_account.account.data.as_slice())?;
+        let woo_amm_pool =
+            &WooAmmPool::try_deserialize(&mut keyed_account.account.data.as_slice())?;
+
+        if let Some(quote_mint) = params.quote_mint {
+            if quote_mint != woo_amm_pool.quote_token_mint {
+                return Err(ParamsError::QuoteMintMismatch {
+                    quote_mint,
+                    pool_quote_mint: woo_amm_pool.quote_token_mint,
+                }
+                .into());
+            }
+        }
+
+        let oracle_guard = OracleGuard {
+            max_conf_ratio: params.max_conf_ratio.map(u128::from),
+            max_staleness: params.max_staleness,
+        };
 
         let wooconfig = woo_amm_pool.wooconfig;
         let token_a_mint = woo_amm_pool.token_mint_a;
@@ -116,7 +636,7 @@ impl Amm for WoofiSwap {
         let token_a_vault = woo_amm_pool.token_vault_a;
         let token_a_feed_account = woo_amm_pool.feed_account_a;
         let token_a_price_update = woo_amm_pool.price_update_a;
-        
+
         let token_b_mint = woo_amm_pool.token_mint_b;
         let token_b_wooracle = woo_amm_pool.wooracle_b;
         let token_b_woopool = woo_amm_pool.woopool_b;
@@ -132,8 +652,9 @@ impl Amm for WoofiSwap {
 
         Ok(WoofiSwap {
             key: keyed_account.key,
-            label: "WoofiSwap".into(),
+            label: params.label.unwrap_or_else(|| "WoofiSwap".into()),
             program_id,
+            cluster,
             usdc_mint,
             token_a_mint,
             token_b_mint,
@@ -152,6 +673,9 @@ impl Amm for WoofiSwap {
             usdc_feed_account,
             usdc_woopool,
             usdc_vault,
+            rebate_to: params.rebate_to,
+            oracle_guard_a: oracle_guard,
+            oracle_guard_b: oracle_guard,
             wooconfig_state: None,
             fee_rate: 0,
             decimals_a: None,
@@ -163,7 +687,10 @@ impl Amm for WoofiSwap {
             woopool_b: None,
             token_b_balance: None,
             usdc_balance: None,
-            clock_ref: amm_context.clock_ref.clone(),       
+            woopool_quote: None,
+            oracle_health_a: None,
+            oracle_health_b: None,
+            clock_ref: amm_context.clock_ref.clone(),
         })
     }
 
@@ -180,7 +707,10 @@ impl Amm for WoofiSwap {
     }
 
     fn get_accounts_to_update(&self) -> Vec<Pubkey> {
-        vec![
+        // everything `update` reads, once each: the quote token accounts coincide with
+        // one side of the pair when it is the quote token
+        let mut accounts: Vec<Pubkey> = Vec::with_capacity(13);
+        for account in [
             self.wooconfig,
             self.token_a_wooracle,
             self.token_a_woopool,
@@ -190,13 +720,28 @@ impl Amm for WoofiSwap {
             self.token_b_woopool,
             self.token_b_price_update,
             self.token_b_vault,
+            self.usdc_woopool,
+            self.usdc_price_update,
             self.usdc_vault,
             sysvar::clock::ID,
-        ]
+        ] {
+            if !accounts.contains(&account) {
+                accounts.push(account);
+            }
+        }
+        accounts
     }
 
     fn update(&mut self, account_map: &AccountMap) -> Result<()> {
-        println!("start update:");
+        // staleness checks below run against the cluster clock of this account snapshot. The
+        // ClockRef from the AmmContext is shared by every market, so the snapshot clock replaces it
+        // with one owned by this instance rather than being written into it; until a clock sysvar
+        // is seen the market follows the shared clock.
+        if let Ok(clock_data) = try_get_account_data(account_map, &sysvar::clock::ID) {
+            let clock: Clock = bincode::deserialize(clock_data)
+                .context("Failed to deserialize sysvar::clock::ID")?;
+            self.clock_ref = ClockRef::from(clock);
+        }
 
         let wooconfig_data = &mut try_get_account_data(account_map, &self.wooconfig)?;
         let wooconfig = WooConfig::try_deserialize(wooconfig_data)?;
@@ -242,12 +787,27 @@ impl Amm for WoofiSwap {
             token_a_wooracle.base_decimals as u32,
         );
 
-        let state_a = get_price::get_state_impl(
+        let health_a = get_price::get_oracle_health(
             &self.clock_ref,
             token_a_wooracle,
             token_a_price_update,
             quote_price_update,
-        )?;
+            &self.oracle_guard_a,
+        );
+        let health_b = get_price::get_oracle_health(
+            &self.clock_ref,
+            token_b_wooracle,
+            token_b_price_update,
+            quote_price_update,
+            &self.oracle_guard_b,
+        );
+
+        // stored before the states are derived from them, so a failed update can still be
+        // explained; a health that could not be computed clears the previous one
+        self.oracle_health_a = health_a.as_ref().ok().copied();
+        self.oracle_health_b = health_b.as_ref().ok().copied();
+
+        let state_a = health_a?.state(token_a_wooracle)?;
 
         let decimals_b = Decimals::new(
             token_b_wooracle.price_decimals as u32,
@@ -255,19 +815,12 @@ impl Amm for WoofiSwap {
             token_b_wooracle.base_decimals as u32,
         );
 
-        let state_b = get_price::get_state_impl(
-            &self.clock_ref,
-            token_b_wooracle,
-            token_b_price_update,
-            quote_price_update,
-        )?;
+        let state_b = health_b?.state(token_b_wooracle)?;
 
         let get_token_balance = |token_vault, woopool| {
             try_get_account_data(account_map, token_vault)
                 .ok()
-                .and_then(|account_data| {
-                    Account::unpack(account_data).ok()
-                })
+                .and_then(|account_data| Account::unpack(account_data).ok())
                 .and_then(|token_account| {
                     if token_account.is_frozen() {
                         None
@@ -289,163 +842,45 @@ impl Amm for WoofiSwap {
         self.decimals_b = decimals_b;
         self.state_b = Some(state_b);
         self.woopool_b = Some(token_b_woopool);
+        self.woopool_quote = Some(usdc_woopool);
 
         Ok(())
     }
 
     fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
-        let wooconfig = self.wooconfig_state.as_ref().context("Missing wooconfig")?;
-        if wooconfig.paused {
-            return Err(anyhow!("Woofi is paused"));
-        }
+        let in_amount = self.quote_in_amount(quote_params)?;
+        self.quote_exact_in(
+            in_amount,
+            &quote_params.input_mint,
+            &quote_params.output_mint,
+        )
+    }
 
-        let (decimals_from,
-            state_from,
-            woopool_from,
-            decimals_to,
-            state_to,
-            woopool_to,
-            token_from_balance,
-            token_to_balance,
-            usdc_balance
-        ) = {
-            if self.token_a_mint == quote_params.input_mint {
-                (
-                    self.decimals_a.as_ref().context("Missing decimals_a")?,
-                    self.state_a.as_ref().context("Missing state_a")?,
-                    self.woopool_a.as_ref().context("Missing woopool_a")?,
-                    self.decimals_b.as_ref().context("Missing decimals_b")?,
-                    self.state_b.as_ref().context("Missing state_b")?,
-                    self.woopool_b.as_ref().context("Missing woopool_b")?,
-                    self.token_a_balance.context("Missing token_b_balance")?,
-                    self.token_b_balance.context("Missing token_b_balance")?,
-                    self.usdc_balance.context("Missing usdc_balance")?
-                )
-            } else {
-                (
-                    self.decimals_b.as_ref().context("Missing decimals_b")?,
-                    self.state_b.as_ref().context("Missing state_b")?,
-                    self.woopool_b.as_ref().context("Missing woopool_b")?,
-                    self.decimals_a.as_ref().context("Missing decimals_a")?,
-                    self.state_a.as_ref().context("Missing state_a")?,
-                    self.woopool_a.as_ref().context("Missing woopool_a")?,
-                    self.token_b_balance.context("Missing token_b_balance")?,
-                    self.token_a_balance.context("Missing token_a_balance")?,
-                    self.usdc_balance.context("Missing usdc_balance")?
-                )
-            }
+    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        let pool_accounts =
+            self.swap_pool_accounts(&swap_params.source_mint, &swap_params.destination_mint)?;
+
+        // rebates go to the swapper unless the market is configured with a dedicated account
+        let user_accounts = SwapUserAccounts {
+            payer: swap_params.token_transfer_authority,
+            token_owner_account_from: swap_params.source_token_account,
+            token_owner_account_to: swap_params.destination_token_account,
+            rebate_to: self
+                .rebate_to
+                .unwrap_or(swap_params.token_transfer_authority),
         };
 
-        let in_amount = quote_params.amount as u128;
-
-        if in_amount <= woopool_from.min_swap_amount {
-            return
-                Ok(Quote {
-                    fee_pct: self.fee_rate.into(),
-                    in_amount: in_amount as u64,
-                    out_amount: 0,
-                    fee_amount: 0,
-                    fee_mint: self.usdc_mint,
-                    ..Quote::default()
-                });
-        }
-
-        let woopool_from_after = token_from_balance.checked_add(in_amount).context("computation overflow")?;
-        if  woopool_from_after > woopool_from.cap_bal {
-            return Err(ErrorCode::BalanceCapExceeds.into());
-        }
-
-        let usdc_amount: u128 = 
-            if quote_params.input_mint == self.usdc_mint {
-                in_amount
-            } else {
-                let (_usdc_amount, _) = swap_math::calc_quote_amount_sell_base(
-                    in_amount,
-                    woopool_from,
-                    decimals_from,
-                    state_from,
-                )?;
-
-                _usdc_amount
-            };
-
-        let swap_fee = checked_mul_div_round_up(usdc_amount, self.fee_rate as u128, ONE_E5_U128)?;
-        let usdc_amount_after_fee = usdc_amount
-            .checked_sub(swap_fee)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        let check_usdc_amount = 
-            // sell base
-            if woopool_to.token_mint == self.usdc_mint {
-                usdc_amount
-            }
-            // sell quote/ base to base 
-            else {
-                swap_fee
-            };
-
-        if usdc_balance < check_usdc_amount {
-            return Err(ErrorCode::NotEnoughOut.into());
-        }
-
-        let to_amount: u128 = 
-            if quote_params.output_mint == self.usdc_mint {
-                usdc_amount_after_fee
-            } else {
-                let (_to_amount, _) = swap_math::calc_base_amount_sell_quote(
-                    usdc_amount_after_fee,
-                    woopool_to,
-                    decimals_to,
-                    state_to,
-                )?;
-                _to_amount
-            };
-
-        if token_to_balance < to_amount {
-            return Err(ErrorCode::NotEnoughOut.into());
-        }
-
-        Ok(Quote {
-            fee_pct: self.fee_rate.into(),
-            in_amount: in_amount as u64,
-            out_amount: to_amount as u64,
-            fee_amount: swap_fee as u64,
-            fee_mint: self.usdc_mint,
-            ..Quote::default()
+        Ok(SwapAndAccountMetas {
+            swap: Swap::Woofi,
+            account_metas: instructions::swap_account_metas(&pool_accounts, &user_accounts),
         })
     }
 
-    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
-        let account_metas = vec![
-            AccountMeta::new(self.wooconfig, false),
-            AccountMeta::new_readonly(spl_token::ID, false),
-            AccountMeta::new(swap_params.token_transfer_authority, true),
-            AccountMeta::new(self.token_a_wooracle, false),
-            AccountMeta::new(self.token_a_woopool, false),
-            AccountMeta::new(swap_params.source_token_account, false),
-            AccountMeta::new(self.token_a_vault, false),
-            AccountMeta::new(self.token_a_price_update, false),
-            AccountMeta::new(self.token_b_wooracle, false),
-            AccountMeta::new(self.token_b_woopool, false),
-            AccountMeta::new(swap_params.destination_token_account, false),
-            AccountMeta::new(self.token_b_vault, false),
-            AccountMeta::new(self.token_b_price_update, false),
-            AccountMeta::new(self.usdc_woopool, false),
-            AccountMeta::new(self.usdc_price_update, false),
-            AccountMeta::new(self.usdc_vault, false),
-            // TODO: confirm with Jupiter about the rebate address
-            // AccountMeta::new(self.rebate_to, false),
-        ];
-
-        // TODO: uncomment this part in prod
-        // Ok(SwapAndAccountMetas {
-        //     swap: Swap::WoofiSwap,
-        //     account_metas
-        // })
-        unimplemented!()
-    }
-
     fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
         Box::new(self.clone())
     }
+
+    fn supports_exact_out(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,101 @@
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{errors::QuoteError, WoofiSwap};
+
+// The constraint that stops a swap from growing any larger
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapLimit {
+    // woopool max_notional_swap
+    MaxNotional,
+    // woopool max_gamma
+    MaxGamma,
+    // source woopool cap_bal
+    BalanceCap,
+    // gamma + spread reaching 1e18
+    SpreadPlusGamma,
+    // quote token vault cannot cover the quote amount or fee
+    QuoteReserves,
+    // destination vault cannot cover the output
+    DestinationReserves,
+    // nothing binds below u64::MAX
+    MaxAmount,
+}
+
+impl SwapLimit {
+    // The limit a failed swap ran into, None for errors that are not size limits
+    pub fn from_error(error: &Error) -> Option<SwapLimit> {
+        match error.downcast_ref::<QuoteError>()? {
+            QuoteError::MaxNotionalExceeded { .. } => Some(SwapLimit::MaxNotional),
+            QuoteError::GammaExceeded { .. } => Some(SwapLimit::MaxGamma),
+            QuoteError::BalanceCapExceeded { .. } => Some(SwapLimit::BalanceCap),
+            QuoteError::SpreadPlusGammaExceeded { .. } => Some(SwapLimit::SpreadPlusGamma),
+            QuoteError::InsufficientQuoteReserves { .. } => Some(SwapLimit::QuoteReserves),
+            QuoteError::InsufficientReserves { .. } => Some(SwapLimit::DestinationReserves),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaxSwapSize {
+    pub in_amount: u128,
+    pub out_amount: u128,
+    // what one more unit of input runs into
+    pub limit: SwapLimit,
+}
+
+impl WoofiSwap {
+    // Largest ExactIn amount from `input_mint` to `output_mint` that passes every check `quote` makes.
+    // Size limits only bind from above, so the inputs that fail with one form a range ending at u64::MAX.
+    pub fn max_swap_size(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<MaxSwapSize> {
+        let limit_at = |in_amount: u128| -> Result<Option<SwapLimit>> {
+            match self.compute_swap(in_amount, input_mint, output_mint) {
+                Ok(_) => Ok(None),
+                Err(error) => match SwapLimit::from_error(&error) {
+                    Some(limit) => Ok(Some(limit)),
+                    // below the minimum swap amount is the other end of the range
+                    None if matches!(
+                        error.downcast_ref::<QuoteError>(),
+                        Some(QuoteError::BelowMinSwapAmount { .. })
+                    ) =>
+                    {
+                        Ok(None)
+                    }
+                    None => Err(error),
+                },
+            }
+        };
+
+        let max_amount = u64::MAX as u128;
+        let (in_amount, limit) = match limit_at(max_amount)? {
+            None => (max_amount, SwapLimit::MaxAmount),
+            Some(mut limit) => {
+                // lo is within the limits, hi is past them and runs into `limit`
+                let mut lo: u128 = 0;
+                let mut hi: u128 = max_amount;
+                while hi - lo > 1 {
+                    let mid = lo + (hi - lo) / 2;
+                    match limit_at(mid)? {
+                        Some(mid_limit) => {
+                            hi = mid;
+                            limit = mid_limit;
+                        }
+                        None => lo = mid,
+                    }
+                }
+                (lo, limit)
+            }
+        };
+
+        // fails when every size within the limits is below the minimum swap amount
+        let swap_result = self.compute_swap(in_amount, input_mint, output_mint)?;
+
+        Ok(MaxSwapSize {
+            in_amount,
+            out_amount: swap_result.out_amount,
+            limit,
+        })
+    }
+}
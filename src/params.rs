@@ -0,0 +1,109 @@
+use serde_json::{Map, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{errors::ParamsError, util::get_pubkey_from_param};
+
+pub const PARAM_REBATE_TO: &str = "rebateTo";
+pub const PARAM_LABEL: &str = "label";
+pub const PARAM_PROGRAM_ID: &str = "programId";
+pub const PARAM_QUOTE_MINT: &str = "quoteMint";
+pub const PARAM_MAX_STALENESS: &str = "maxStalenessSeconds";
+pub const PARAM_MAX_CONF_RATIO: &str = "maxConfRatio";
+
+const PARAMS: [&str; 6] = [
+    PARAM_REBATE_TO,
+    PARAM_LABEL,
+    PARAM_PROGRAM_ID,
+    PARAM_QUOTE_MINT,
+    PARAM_MAX_STALENESS,
+    PARAM_MAX_CONF_RATIO,
+];
+
+// WooFi settings carried in a market-cache entry's `KeyedAccount.params`. Every key is optional
+// and unknown keys are rejected, so a typo does not silently fall back to the defaults:
+//
+// {
+//   "rebateTo": "<pubkey>",       rebate account for swap instructions, instead of the swapper
+//   "label": "WooFi SOL/USDC",    replaces the "WoofiSwap" label
+//   "programId": "<pubkey>",      program that must own the pool; allows deployments other than
+//                                 mainnet-beta and devnet
+//   "quoteMint": "<pubkey>",      quote token the pool must price against
+//   "maxStalenessSeconds": 30,    caps the wooracle stale_duration and pyth maximum_age
+//   "maxConfRatio": 10000000000000000
+//                                 max pyth conf / price in 1e18 units (this is 1%), as an integer;
+//                                 replaces the default, which is each wooracle's bound
+// }
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WoofiParams {
+    pub rebate_to: Option<Pubkey>,
+    pub label: Option<String>,
+    pub program_id: Option<Pubkey>,
+    pub quote_mint: Option<Pubkey>,
+    pub max_staleness: Option<u64>,
+    pub max_conf_ratio: Option<u64>,
+}
+
+impl WoofiParams {
+    // Missing or null params are the defaults
+    pub fn parse(params: Option<&Value>) -> Result<Self, ParamsError> {
+        let param_map = match params {
+            None | Some(Value::Null) => return Ok(WoofiParams::default()),
+            Some(Value::Object(param_map)) => param_map,
+            Some(_) => return Err(ParamsError::NotAnObject),
+        };
+
+        if let Some(key) = param_map.keys().find(|key| !PARAMS.contains(&key.as_str())) {
+            return Err(ParamsError::UnknownKey(key.clone()));
+        }
+
+        let label = match param_map.get(PARAM_LABEL) {
+            None => None,
+            Some(Value::String(label)) if !label.is_empty() => Some(label.clone()),
+            Some(_) => return Err(invalid(PARAM_LABEL, "expected a non-empty string")),
+        };
+
+        let max_staleness = u64_param(param_map, PARAM_MAX_STALENESS)?;
+        let max_conf_ratio = u64_param(param_map, PARAM_MAX_CONF_RATIO)?;
+
+        Ok(WoofiParams {
+            rebate_to: pubkey_param(param_map, PARAM_REBATE_TO)?,
+            label,
+            program_id: pubkey_param(param_map, PARAM_PROGRAM_ID)?,
+            quote_mint: pubkey_param(param_map, PARAM_QUOTE_MINT)?,
+            max_staleness,
+            max_conf_ratio,
+        })
+    }
+}
+
+fn u64_param(
+    param_map: &Map<String, Value>,
+    key: &'static str,
+) -> Result<Option<u64>, ParamsError> {
+    match param_map.get(key) {
+        None => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| invalid(key, "expected a non-negative integer")),
+    }
+}
+
+fn pubkey_param(
+    param_map: &Map<String, Value>,
+    key: &'static str,
+) -> Result<Option<Pubkey>, ParamsError> {
+    if !param_map.contains_key(key) {
+        return Ok(None);
+    }
+    get_pubkey_from_param(param_map, key.into())
+        .map(Some)
+        .map_err(|error| invalid(key, &error.to_string()))
+}
+
+fn invalid(key: &'static str, reason: &str) -> ParamsError {
+    ParamsError::InvalidValue {
+        key,
+        reason: reason.into(),
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use jupiter_amm_interface::{Quote, QuoteParams};
+
+use crate::{
+    errors::{ErrorCode, QuoteError},
+    util::{GetStateResult, OracleHealth},
+    SwapResult, WoofiSwap,
+};
+
+impl WoofiSwap {
+    // Quotes `quote_params` and applies the swap to the cached state as the program's swap instruction
+    // would: vaults move by the in and out amounts, the fee is booked to the quote pool's unclaimed_fee
+    // and each base wooracle is posted its new price. Following quotes see the pool after this swap.
+    pub fn apply_swap(&mut self, quote_params: &QuoteParams) -> Result<Quote> {
+        let in_amount = self.quote_in_amount(quote_params)?;
+        let swap_result = self.compute_swap(
+            in_amount,
+            &quote_params.input_mint,
+            &quote_params.output_mint,
+        )?;
+        let quote = self.swap_result_quote(&swap_result)?;
+
+        self.apply_swap_result(&swap_result, quote_params.input_mint == self.token_a_mint)?;
+
+        Ok(quote)
+    }
+
+    fn apply_swap_result(&mut self, swap_result: &SwapResult, from_a: bool) -> Result<()> {
+        let SwapResult {
+            in_amount,
+            out_amount,
+            swap_fee,
+            new_price_from,
+            new_price_to,
+            ..
+        } = *swap_result;
+
+        let (new_price_a, new_price_b) = if from_a {
+            (new_price_from, new_price_to)
+        } else {
+            (new_price_to, new_price_from)
+        };
+        let now = self.clock_ref.unix_timestamp.load(Ordering::Relaxed);
+        if let Some(new_price) = new_price_a {
+            post_price(&mut self.state_a, &mut self.oracle_health_a, new_price, now)?;
+        }
+        if let Some(new_price) = new_price_b {
+            post_price(&mut self.state_b, &mut self.oracle_health_b, new_price, now)?;
+        }
+
+        // vault amounts
+        let (balance_from, balance_to) = if from_a {
+            (&mut self.token_a_balance, &mut self.token_b_balance)
+        } else {
+            (&mut self.token_b_balance, &mut self.token_a_balance)
+        };
+        adjust_balance(balance_from, in_amount, 0)?;
+        adjust_balance(balance_to, 0, out_amount)?;
+
+        // the fee stays in the quote vault but is no longer available to swaps
+        let usdc_mint = self.usdc_mint;
+        for woopool in [
+            &mut self.woopool_quote,
+            &mut self.woopool_a,
+            &mut self.woopool_b,
+        ] {
+            if let Some(woopool) = woopool
+                .as_mut()
+                .filter(|woopool| woopool.token_mint == usdc_mint)
+            {
+                woopool.unclaimed_fee = woopool
+                    .unclaimed_fee
+                    .checked_add(swap_fee)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+        if self.token_a_mint == self.usdc_mint {
+            adjust_balance(&mut self.token_a_balance, 0, swap_fee)?;
+            self.usdc_balance = self.token_a_balance;
+        } else if self.token_b_mint == self.usdc_mint {
+            adjust_balance(&mut self.token_b_balance, 0, swap_fee)?;
+            self.usdc_balance = self.token_b_balance;
+        } else {
+            adjust_balance(&mut self.usdc_balance, 0, swap_fee)?;
+        }
+
+        Ok(())
+    }
+}
+
+// The next swap re-runs the oracle checks against the posted price, which may leave the Pyth bound
+fn post_price(
+    state: &mut Option<GetStateResult>,
+    health: &mut Option<OracleHealth>,
+    new_price: u128,
+    now: i64,
+) -> Result<()> {
+    let health = health
+        .as_mut()
+        .ok_or(QuoteError::Uninitialized("oracle_health"))?;
+    let state = state.as_mut().ok_or(QuoteError::Uninitialized("state"))?;
+
+    *health = health.with_wo_price(new_price, now);
+    state.price_out = if health.feasible { new_price } else { 0 };
+    state.feasible_out = health.feasible;
+
+    Ok(())
+}
+
+fn adjust_balance(balance: &mut Option<u128>, add: u128, sub: u128) -> Result<()> {
+    let current = balance.ok_or(QuoteError::Uninitialized("token balance"))?;
+    *balance = Some(
+        current
+            .checked_add(add)
+            .and_then(|v| v.checked_sub(sub))
+            .ok_or(ErrorCode::MathOverflow)?,
+    );
+    Ok(())
+}
@@ -1,9 +1,11 @@
+pub mod swap_event;
+pub mod wooammpool;
 pub mod wooconfig;
 pub mod woopool;
 pub mod wooracle;
-pub mod wooammpool;
 
+pub use swap_event::*;
+pub use wooammpool::*;
 pub use wooconfig::*;
 pub use woopool::*;
 pub use wooracle::*;
-pub use wooammpool::*;
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::{borsh, event, AnchorDeserialize, AnchorSerialize, Pubkey};
+
+// Emitted by the program's `swap` as a "Program data:" log. Amounts are in token units,
+// swap_vol and swap_fee in the quote token.
+#[event]
+#[derive(Debug, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub sender: Pubkey,
+    pub from_token_mint: Pubkey,
+    pub to_token_mint: Pubkey,
+    pub from_amount: u128,
+    pub to_amount: u128,
+    pub from_account: Pubkey,
+    pub to_account: Pubkey,
+    pub rebate_to: Pubkey,
+    pub swap_vol: u128,
+    pub swap_fee: u128,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::AccountSerialize;
+use anchor_spl::token::spl_token::state::{Account as TokenAccount, AccountState};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use solana_sdk::{
+    account::Account, clock::Clock, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar,
+};
+
+// Discriminated, Anchor serialized account owned by `owner`
+pub fn anchor_account<T: AccountSerialize>(state: &T, owner: &Pubkey) -> Account {
+    let mut data = Vec::new();
+    state
+        .try_serialize(&mut data)
+        .expect("anchor account serialization");
+    account_with_data(data, owner)
+}
+
+pub fn price_update_account(price_update: &PriceUpdateV2) -> Account {
+    anchor_account(price_update, &pyth_solana_receiver_sdk::ID)
+}
+
+pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let token_account = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    };
+
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount::pack(token_account, &mut data).expect("token account packing");
+    account_with_data(data, &spl_token::ID)
+}
+
+pub fn clock_account(clock: &Clock) -> Account {
+    let data = bincode::serialize(clock).expect("clock serialization");
+    account_with_data(data, &sysvar::ID)
+}
+
+fn account_with_data(data: Vec<u8>, owner: &Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,300 @@
+use pyth_solana_receiver_sdk::price_update::{PriceFeedMessage, PriceUpdateV2, VerificationLevel};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::{
+    state::{WooConfig, WooPool, Wooracle},
+    testing::{anchor_account, price_update_account},
+};
+
+pub struct WooConfigBuilder {
+    wooconfig: WooConfig,
+}
+
+impl Default for WooConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WooConfigBuilder {
+    pub fn new() -> Self {
+        WooConfigBuilder {
+            wooconfig: WooConfig {
+                authority: Pubkey::default(),
+                paused: false,
+                woopool_admin_authority: vec![],
+                wooracle_admin_authority: vec![],
+                fee_authority: vec![],
+                guardian_authority: vec![],
+                pause_authority: vec![],
+                lending_manager_authority: vec![],
+                supercharger_vault_whitelist: vec![],
+                new_authority: Pubkey::default(),
+            },
+        }
+    }
+
+    pub fn authority(mut self, authority: Pubkey) -> Self {
+        self.wooconfig.authority = authority;
+        self
+    }
+
+    pub fn paused(mut self, paused: bool) -> Self {
+        self.wooconfig.paused = paused;
+        self
+    }
+
+    pub fn build(self) -> WooConfig {
+        self.wooconfig
+    }
+
+    pub fn into_account(self, program_id: &Pubkey) -> Account {
+        anchor_account(&self.wooconfig, program_id)
+    }
+}
+
+pub struct WooracleBuilder {
+    wooracle: Wooracle,
+}
+
+impl WooracleBuilder {
+    pub fn new(
+        wooconfig: Pubkey,
+        token_mint: Pubkey,
+        feed_account: Pubkey,
+        price_update: Pubkey,
+    ) -> Self {
+        WooracleBuilder {
+            wooracle: Wooracle {
+                wooconfig,
+                authority: Pubkey::default(),
+                token_mint,
+                feed_account,
+                price_update,
+                maximum_age: 60,
+                price_decimals: 8,
+                quote_decimals: 6,
+                base_decimals: 9,
+                updated_at: 0,
+                stale_duration: 120,
+                bound: 10_000_000_000_000_000, // 1%
+                price: 0,
+                coeff: 0,
+                spread: 0,
+                range_min: 0,
+                range_max: u128::MAX,
+                quote_token_mint: Pubkey::default(),
+                quote_feed_account: Pubkey::default(),
+                quote_price_update: Pubkey::default(),
+            },
+        }
+    }
+
+    pub fn quote(
+        mut self,
+        quote_token_mint: Pubkey,
+        quote_feed_account: Pubkey,
+        quote_price_update: Pubkey,
+    ) -> Self {
+        self.wooracle.quote_token_mint = quote_token_mint;
+        self.wooracle.quote_feed_account = quote_feed_account;
+        self.wooracle.quote_price_update = quote_price_update;
+        self
+    }
+
+    pub fn decimals(mut self, price_decimals: u8, quote_decimals: u8, base_decimals: u8) -> Self {
+        self.wooracle.price_decimals = price_decimals;
+        self.wooracle.quote_decimals = quote_decimals;
+        self.wooracle.base_decimals = base_decimals;
+        self
+    }
+
+    pub fn price(mut self, price: u128) -> Self {
+        self.wooracle.price = price;
+        self
+    }
+
+    pub fn updated_at(mut self, updated_at: i64) -> Self {
+        self.wooracle.updated_at = updated_at;
+        self
+    }
+
+    pub fn stale_duration(mut self, stale_duration: i64) -> Self {
+        self.wooracle.stale_duration = stale_duration;
+        self
+    }
+
+    pub fn maximum_age(mut self, maximum_age: u64) -> Self {
+        self.wooracle.maximum_age = maximum_age;
+        self
+    }
+
+    pub fn bound(mut self, bound: u64) -> Self {
+        self.wooracle.bound = bound;
+        self
+    }
+
+    pub fn coeff(mut self, coeff: u64) -> Self {
+        self.wooracle.coeff = coeff;
+        self
+    }
+
+    pub fn spread(mut self, spread: u64) -> Self {
+        self.wooracle.spread = spread;
+        self
+    }
+
+    pub fn range(mut self, range_min: u128, range_max: u128) -> Self {
+        self.wooracle.range_min = range_min;
+        self.wooracle.range_max = range_max;
+        self
+    }
+
+    pub fn build(self) -> Wooracle {
+        self.wooracle
+    }
+
+    pub fn into_account(self, program_id: &Pubkey) -> Account {
+        anchor_account(&self.wooracle, program_id)
+    }
+}
+
+pub struct WooPoolBuilder {
+    woopool: WooPool,
+}
+
+impl WooPoolBuilder {
+    pub fn new(
+        wooconfig: Pubkey,
+        wooracle: Pubkey,
+        token_mint: Pubkey,
+        quote_token_mint: Pubkey,
+        token_vault: Pubkey,
+    ) -> Self {
+        WooPoolBuilder {
+            woopool: WooPool {
+                wooconfig,
+                woopool_bump: [0],
+                authority: Pubkey::default(),
+                wooracle,
+                fee_rate: 25,
+                max_gamma: 10_000_000_000_000_000, // 1%
+                max_notional_swap: 1_000_000_000_000,
+                cap_bal: u64::MAX as u128,
+                min_swap_amount: 0,
+                unclaimed_fee: 0,
+                token_mint,
+                token_vault,
+                quote_token_mint,
+                base_decimals: 9,
+            },
+        }
+    }
+
+    pub fn fee_rate(mut self, fee_rate: u16) -> Self {
+        self.woopool.fee_rate = fee_rate;
+        self
+    }
+
+    pub fn max_gamma(mut self, max_gamma: u128) -> Self {
+        self.woopool.max_gamma = max_gamma;
+        self
+    }
+
+    pub fn max_notional_swap(mut self, max_notional_swap: u128) -> Self {
+        self.woopool.max_notional_swap = max_notional_swap;
+        self
+    }
+
+    pub fn cap_bal(mut self, cap_bal: u128) -> Self {
+        self.woopool.cap_bal = cap_bal;
+        self
+    }
+
+    pub fn min_swap_amount(mut self, min_swap_amount: u128) -> Self {
+        self.woopool.min_swap_amount = min_swap_amount;
+        self
+    }
+
+    pub fn unclaimed_fee(mut self, unclaimed_fee: u128) -> Self {
+        self.woopool.unclaimed_fee = unclaimed_fee;
+        self
+    }
+
+    pub fn base_decimals(mut self, base_decimals: u8) -> Self {
+        self.woopool.base_decimals = base_decimals;
+        self
+    }
+
+    pub fn build(self) -> WooPool {
+        self.woopool
+    }
+
+    pub fn into_account(self, program_id: &Pubkey) -> Account {
+        anchor_account(&self.woopool, program_id)
+    }
+}
+
+// Pyth pull oracle update; the feed id is the wooracle `feed_account` bytes, as `get_price_impl` expects
+pub struct PriceUpdateBuilder {
+    price_update: PriceUpdateV2,
+}
+
+impl PriceUpdateBuilder {
+    pub fn new(feed_account: Pubkey) -> Self {
+        PriceUpdateBuilder {
+            price_update: PriceUpdateV2 {
+                write_authority: Pubkey::default(),
+                verification_level: VerificationLevel::Full,
+                price_message: PriceFeedMessage {
+                    feed_id: feed_account.to_bytes(),
+                    price: 0,
+                    conf: 0,
+                    exponent: -8,
+                    publish_time: 0,
+                    prev_publish_time: 0,
+                    ema_price: 0,
+                    ema_conf: 0,
+                },
+                posted_slot: 0,
+            },
+        }
+    }
+
+    pub fn price(mut self, price: i64, exponent: i32) -> Self {
+        self.price_update.price_message.price = price;
+        self.price_update.price_message.ema_price = price;
+        self.price_update.price_message.exponent = exponent;
+        self
+    }
+
+    pub fn conf(mut self, conf: u64) -> Self {
+        self.price_update.price_message.conf = conf;
+        self.price_update.price_message.ema_conf = conf;
+        self
+    }
+
+    pub fn publish_time(mut self, publish_time: i64) -> Self {
+        self.price_update.price_message.publish_time = publish_time;
+        self.price_update.price_message.prev_publish_time = publish_time.saturating_sub(1);
+        self
+    }
+
+    pub fn posted_slot(mut self, posted_slot: u64) -> Self {
+        self.price_update.posted_slot = posted_slot;
+        self
+    }
+
+    pub fn verification_level(mut self, verification_level: VerificationLevel) -> Self {
+        self.price_update.verification_level = verification_level;
+        self
+    }
+
+    pub fn build(self) -> PriceUpdateV2 {
+        self.price_update
+    }
+
+    pub fn into_account(self) -> Account {
+        price_update_account(&self.price_update)
+    }
+}
@@ -0,0 +1,357 @@
+use anyhow::Result;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use solana_sdk::{clock::Clock, pubkey::Pubkey, sysvar};
+
+use crate::{
+    cluster::WoofiDeployment,
+    state::{WooAmmPool, WooConfig, WooPool, Wooracle},
+    testing::*,
+    util::{
+        get_wooammpool_address, get_wooconfig_address, get_woopool_address, get_wooracle_address,
+        SOL, USDC,
+    },
+    WoofiSwap,
+};
+
+pub const FIXTURE_UNIX_TIMESTAMP: i64 = 1_730_000_000;
+pub const FIXTURE_SLOT: u64 = 300_000_000;
+pub const FIXTURE_EPOCH: u64 = 700;
+
+// Stand-in mint for a second base token
+pub const FIXTURE_WOO: Pubkey = Pubkey::new_from_array([7; 32]);
+
+// Token listed in a fixture market, priced in 8 decimals against the quote token
+#[derive(Clone, Copy, Debug)]
+pub struct TokenSpec {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub price: i64,
+    pub coeff: u64,
+    pub spread: u64,
+    pub vault_amount: u64,
+}
+
+impl TokenSpec {
+    pub fn sol() -> Self {
+        TokenSpec {
+            mint: SOL,
+            decimals: 9,
+            price: 15_000_000_000,
+            coeff: 1_000_000_000,
+            spread: 100_000_000_000_000,
+            vault_amount: 10_000_000_000_000,
+        }
+    }
+
+    pub fn usdc() -> Self {
+        TokenSpec {
+            mint: USDC,
+            decimals: 6,
+            price: 100_000_000,
+            coeff: 0,
+            spread: 0,
+            vault_amount: 2_000_000_000_000,
+        }
+    }
+
+    pub fn woo() -> Self {
+        TokenSpec {
+            mint: FIXTURE_WOO,
+            decimals: 6,
+            price: 25_000_000,
+            coeff: 5_000_000_000,
+            spread: 300_000_000_000_000,
+            vault_amount: 10_000_000_000_000,
+        }
+    }
+}
+
+// All accounts backing one token of a market. Fields are plain state so tests can tweak them
+// before calling `MarketFixture::account_map`.
+#[derive(Clone)]
+pub struct TokenFixture {
+    pub mint: Pubkey,
+    pub feed_account: Pubkey,
+    pub price_update_address: Pubkey,
+    pub wooracle_address: Pubkey,
+    pub woopool_address: Pubkey,
+    pub vault_address: Pubkey,
+    pub wooracle: Wooracle,
+    pub woopool: WooPool,
+    pub price_update: PriceUpdateV2,
+    pub vault_amount: u64,
+}
+
+impl TokenFixture {
+    pub fn new(
+        program_id: &Pubkey,
+        wooconfig: &Pubkey,
+        token: &TokenSpec,
+        quote: &TokenSpec,
+        clock: &Clock,
+    ) -> Self {
+        let feed_account = fixture_address(&token.mint, "feed_account", program_id);
+        let price_update_address = fixture_address(&token.mint, "price_update", program_id);
+        let vault_address = fixture_address(&token.mint, "token_vault", program_id);
+        let wooracle_address = get_wooracle_address(
+            wooconfig,
+            &token.mint,
+            &feed_account,
+            &price_update_address,
+            program_id,
+        )
+        .0;
+        let woopool_address =
+            get_woopool_address(wooconfig, &token.mint, &quote.mint, program_id).0;
+
+        let price = token.price as u128;
+        let wooracle =
+            WooracleBuilder::new(*wooconfig, token.mint, feed_account, price_update_address)
+                .quote(
+                    quote.mint,
+                    fixture_address(&quote.mint, "feed_account", program_id),
+                    fixture_address(&quote.mint, "price_update", program_id),
+                )
+                .decimals(8, quote.decimals, token.decimals)
+                .price(price)
+                .updated_at(clock.unix_timestamp)
+                .coeff(token.coeff)
+                .spread(token.spread)
+                .range(price / 10, price * 10)
+                .build();
+
+        let woopool = WooPoolBuilder::new(
+            *wooconfig,
+            wooracle_address,
+            token.mint,
+            quote.mint,
+            vault_address,
+        )
+        .base_decimals(token.decimals)
+        .build();
+
+        let price_update = PriceUpdateBuilder::new(feed_account)
+            .price(token.price, -8)
+            .conf((token.price / 2000) as u64)
+            .publish_time(clock.unix_timestamp)
+            .posted_slot(clock.slot)
+            .build();
+
+        TokenFixture {
+            mint: token.mint,
+            feed_account,
+            price_update_address,
+            wooracle_address,
+            woopool_address,
+            vault_address,
+            wooracle,
+            woopool,
+            price_update,
+            vault_amount: token.vault_amount,
+        }
+    }
+
+    pub fn insert_accounts(&self, program_id: &Pubkey, account_map: &mut AccountMap) {
+        account_map.insert(
+            self.wooracle_address,
+            anchor_account(&self.wooracle, program_id),
+        );
+        account_map.insert(
+            self.woopool_address,
+            anchor_account(&self.woopool, program_id),
+        );
+        account_map.insert(
+            self.price_update_address,
+            price_update_account(&self.price_update),
+        );
+        account_map.insert(
+            self.vault_address,
+            token_account(&self.mint, &self.woopool_address, self.vault_amount),
+        );
+    }
+}
+
+// A complete WooFi market. When token A or B is the quote token, edit that side rather than
+// `quote`, since it is written to the account map last.
+#[derive(Clone)]
+pub struct MarketFixture {
+    pub program_id: Pubkey,
+    pub wooconfig_address: Pubkey,
+    pub wooconfig: WooConfig,
+    pub pool_address: Pubkey,
+    pub pool_bump: u8,
+    pub token_a: TokenFixture,
+    pub token_b: TokenFixture,
+    pub quote: TokenFixture,
+    pub clock: Clock,
+}
+
+impl MarketFixture {
+    pub fn new(
+        program_id: Pubkey,
+        token_a: TokenSpec,
+        token_b: TokenSpec,
+        quote: TokenSpec,
+    ) -> Self {
+        let clock = Clock {
+            slot: FIXTURE_SLOT,
+            epoch_start_timestamp: FIXTURE_UNIX_TIMESTAMP - 86_400,
+            epoch: FIXTURE_EPOCH,
+            leader_schedule_epoch: FIXTURE_EPOCH + 1,
+            unix_timestamp: FIXTURE_UNIX_TIMESTAMP,
+        };
+
+        let wooconfig_address = get_wooconfig_address(&program_id).0;
+        let (pool_address, pool_bump) = get_wooammpool_address(
+            &wooconfig_address,
+            &token_a.mint,
+            &token_b.mint,
+            &program_id,
+        );
+
+        MarketFixture {
+            program_id,
+            wooconfig_address,
+            wooconfig: WooConfigBuilder::new().build(),
+            pool_address,
+            pool_bump,
+            token_a: TokenFixture::new(&program_id, &wooconfig_address, &token_a, &quote, &clock),
+            token_b: TokenFixture::new(&program_id, &wooconfig_address, &token_b, &quote, &clock),
+            quote: TokenFixture::new(&program_id, &wooconfig_address, &quote, &quote, &clock),
+            clock,
+        }
+    }
+
+    pub fn sol_usdc() -> Self {
+        Self::new(
+            crate::id(),
+            TokenSpec::sol(),
+            TokenSpec::usdc(),
+            TokenSpec::usdc(),
+        )
+    }
+
+    // SOL/USDC under `deployment`'s program id and quote mint
+    pub fn sol_usdc_on(deployment: &WoofiDeployment) -> Self {
+        let usdc = TokenSpec {
+            mint: deployment.usdc_mint,
+            ..TokenSpec::usdc()
+        };
+        Self::new(deployment.program_id, TokenSpec::sol(), usdc, usdc)
+    }
+
+    pub fn sol_woo() -> Self {
+        Self::new(
+            crate::id(),
+            TokenSpec::sol(),
+            TokenSpec::woo(),
+            TokenSpec::usdc(),
+        )
+    }
+
+    // Moves the clock sysvar forward, at roughly 400ms per slot
+    pub fn advance_clock(&mut self, seconds: i64) {
+        self.clock.unix_timestamp += seconds;
+        self.clock.slot += (seconds as u64) * 5 / 2;
+    }
+
+    // Republishes every Pyth price at the current clock
+    pub fn refresh_price_updates(&mut self) {
+        for token in [&mut self.token_a, &mut self.token_b, &mut self.quote] {
+            token.price_update.price_message.prev_publish_time =
+                token.price_update.price_message.publish_time;
+            token.price_update.price_message.publish_time = self.clock.unix_timestamp;
+            token.price_update.posted_slot = self.clock.slot;
+        }
+    }
+
+    pub fn woo_amm_pool(&self) -> WooAmmPool {
+        WooAmmPool {
+            wooconfig: self.wooconfig_address,
+            wooammpool_bump: [self.pool_bump],
+            authority: Pubkey::default(),
+            wooracle_a: self.token_a.wooracle_address,
+            woopool_a: self.token_a.woopool_address,
+            feed_account_a: self.token_a.feed_account,
+            price_update_a: self.token_a.price_update_address,
+            token_mint_a: self.token_a.mint,
+            token_vault_a: self.token_a.vault_address,
+            wooracle_b: self.token_b.wooracle_address,
+            woopool_b: self.token_b.woopool_address,
+            feed_account_b: self.token_b.feed_account,
+            price_update_b: self.token_b.price_update_address,
+            token_mint_b: self.token_b.mint,
+            token_vault_b: self.token_b.vault_address,
+            quote_token_mint: self.quote.mint,
+            quote_feed_account: self.quote.feed_account,
+            quote_price_update: self.quote.price_update_address,
+            quote_woopool: self.quote.woopool_address,
+            quote_vault: self.quote.vault_address,
+        }
+    }
+
+    pub fn keyed_account(&self) -> KeyedAccount {
+        KeyedAccount {
+            key: self.pool_address,
+            account: anchor_account(&self.woo_amm_pool(), &self.program_id),
+            params: None,
+        }
+    }
+
+    pub fn account_map(&self) -> AccountMap {
+        let mut account_map = AccountMap::default();
+        account_map.insert(
+            self.wooconfig_address,
+            anchor_account(&self.wooconfig, &self.program_id),
+        );
+        self.quote
+            .insert_accounts(&self.program_id, &mut account_map);
+        self.token_a
+            .insert_accounts(&self.program_id, &mut account_map);
+        self.token_b
+            .insert_accounts(&self.program_id, &mut account_map);
+        account_map.insert(sysvar::clock::ID, clock_account(&self.clock));
+        account_map
+    }
+
+    pub fn amm_context(&self) -> AmmContext {
+        AmmContext {
+            clock_ref: ClockRef::from(self.clock.clone()),
+        }
+    }
+
+    // from_keyed_account followed by update, ready to quote
+    pub fn woofi_swap(&self) -> Result<WoofiSwap> {
+        let mut woofi_swap =
+            WoofiSwap::from_keyed_account(&self.keyed_account(), &self.amm_context())?;
+        woofi_swap.update(&self.account_map())?;
+        Ok(woofi_swap)
+    }
+}
+
+pub fn quote_params(
+    amount: u64,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    swap_mode: SwapMode,
+) -> QuoteParams {
+    QuoteParams {
+        amount,
+        input_mint,
+        output_mint,
+        swap_mode,
+    }
+}
+
+// 1 SOL for USDC, the quote most tests start from
+pub fn sell_sol() -> QuoteParams {
+    quote_params(1_000_000_000, SOL, USDC, SwapMode::ExactIn)
+}
+
+// Deterministic stand-in for accounts that are not program PDAs (Pyth accounts, vaults)
+fn fixture_address(mint: &Pubkey, seed: &str, program_id: &Pubkey) -> Pubkey {
+    Pubkey::create_with_seed(mint, seed, program_id).expect("fixture address seed")
+}
@@ -0,0 +1,11 @@
+pub mod accounts;
+pub mod builders;
+pub mod market;
+pub mod recorded_swap;
+pub mod snapshot;
+
+pub use accounts::*;
+pub use builders::*;
+pub use market::*;
+pub use recorded_swap::*;
+pub use snapshot::*;
@@ -0,0 +1,114 @@
+use std::{fs, path::Path, str::FromStr};
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use jupiter_amm_interface::{Amm, Quote, QuoteParams, SwapMode};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{state::SwapEvent, testing::MarketSnapshot};
+
+pub const RECORDED_SWAP_VERSION: u32 = 1;
+
+// A swap the program executed, with the market accounts as they were before it landed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedSwap {
+    pub version: u32,
+    pub signature: String,
+    // slot the transaction landed in; no transaction touching the swap's wooracles or woopools
+    // landed between `market.slot` and this one
+    pub slot: u64,
+    pub market: MarketSnapshot,
+    pub input_mint: String,
+    pub output_mint: String,
+    // amounts from the program's SwapEvent
+    pub from_amount: u64,
+    pub to_amount: u64,
+    pub swap_fee: u64,
+}
+
+impl RecordedSwap {
+    pub fn new(
+        signature: &str,
+        slot: u64,
+        market: MarketSnapshot,
+        event: &SwapEvent,
+    ) -> Result<Self> {
+        Ok(RecordedSwap {
+            version: RECORDED_SWAP_VERSION,
+            signature: signature.into(),
+            slot,
+            market,
+            input_mint: event.from_token_mint.to_string(),
+            output_mint: event.to_token_mint.to_string(),
+            from_amount: u64::try_from(event.from_amount)?,
+            to_amount: u64::try_from(event.to_amount)?,
+            swap_fee: u64::try_from(event.swap_fee)?,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let recorded: RecordedSwap = serde_json::from_str(&json)?;
+
+        if recorded.version != RECORDED_SWAP_VERSION {
+            return Err(anyhow!(
+                "Unsupported recorded swap version {}",
+                recorded.version
+            ));
+        }
+        match recorded.market.slot {
+            Some(market_slot) if recorded.slot > market_slot => {}
+            market_slot => {
+                return Err(anyhow!(
+                    "Swap at slot {} does not follow the accounts read at slot {:?}",
+                    recorded.slot,
+                    market_slot
+                ))
+            }
+        }
+        recorded
+            .market
+            .validate()
+            .with_context(|| format!("Invalid market in {}", path.display()))?;
+
+        Ok(recorded)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    // The program's swap takes an exact from_amount
+    pub fn quote_params(&self) -> Result<QuoteParams> {
+        Ok(QuoteParams {
+            amount: self.from_amount,
+            input_mint: Pubkey::from_str(&self.input_mint)?,
+            output_mint: Pubkey::from_str(&self.output_mint)?,
+            swap_mode: SwapMode::ExactIn,
+        })
+    }
+
+    // Quotes the swap against the accounts it ran on
+    pub fn replay(&self) -> Result<Quote> {
+        self.market.woofi_swap()?.quote(&self.quote_params()?)
+    }
+}
+
+// Every SwapEvent among the "Program data:" lines of a transaction's logs
+pub fn swap_events(logs: &[String]) -> Vec<SwapEvent> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter(|data| data.get(..8) == Some(&SwapEvent::DISCRIMINATOR[..]))
+        .filter_map(|data| SwapEvent::deserialize(&mut &data[8..]).ok())
+        .collect()
+}
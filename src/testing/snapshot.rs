@@ -0,0 +1,267 @@
+use std::{collections::HashSet, fs, path::Path, str::FromStr};
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, clock::Clock, pubkey::Pubkey, sysvar};
+
+use crate::{
+    cluster::Cluster,
+    util::{SOL, USDC},
+    WoofiSwap,
+};
+
+pub const SNAPSHOT_VERSION: u32 = 2;
+pub const SOURCE_RPC: &str = "rpc";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountSnapshot {
+    pub pubkey: String,
+    pub owner: String,
+    pub lamports: u64,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    // base64 encoded account data
+    pub data: String,
+}
+
+impl AccountSnapshot {
+    pub fn new(pubkey: &Pubkey, account: &Account) -> Self {
+        AccountSnapshot {
+            pubkey: pubkey.to_string(),
+            owner: account.owner.to_string(),
+            lamports: account.lamports,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: STANDARD.encode(&account.data),
+        }
+    }
+
+    pub fn pubkey(&self) -> Result<Pubkey> {
+        Ok(Pubkey::from_str(&self.pubkey)?)
+    }
+
+    pub fn account(&self) -> Result<Account> {
+        Ok(Account {
+            lamports: self.lamports,
+            data: STANDARD.decode(&self.data)?,
+            owner: Pubkey::from_str(&self.owner)?,
+            executable: self.executable,
+            rent_epoch: self.rent_epoch,
+        })
+    }
+}
+
+// A quote request and the amounts it produced when the snapshot was taken
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedQuote {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount: u64,
+    // "ExactIn" or "ExactOut"
+    pub swap_mode: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee_amount: u64,
+}
+
+impl RecordedQuote {
+    pub fn record(woofi_swap: &WoofiSwap, quote_params: &QuoteParams) -> Result<Self> {
+        let quote = woofi_swap.quote(quote_params)?;
+
+        Ok(RecordedQuote {
+            input_mint: quote_params.input_mint.to_string(),
+            output_mint: quote_params.output_mint.to_string(),
+            amount: quote_params.amount,
+            swap_mode: match quote_params.swap_mode {
+                SwapMode::ExactIn => "ExactIn".into(),
+                SwapMode::ExactOut => "ExactOut".into(),
+            },
+            in_amount: quote.in_amount,
+            out_amount: quote.out_amount,
+            fee_amount: quote.fee_amount,
+        })
+    }
+
+    pub fn quote_params(&self) -> Result<QuoteParams> {
+        Ok(QuoteParams {
+            amount: self.amount,
+            input_mint: Pubkey::from_str(&self.input_mint)?,
+            output_mint: Pubkey::from_str(&self.output_mint)?,
+            swap_mode: match self.swap_mode.as_str() {
+                "ExactIn" => SwapMode::ExactIn,
+                "ExactOut" => SwapMode::ExactOut,
+                other => return Err(anyhow!("Unknown swap mode {}", other)),
+            },
+        })
+    }
+}
+
+// Every account a WoofiSwap market reads, so it can be quoted without network access
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MarketSnapshot {
+    pub version: u32,
+    pub label: String,
+    // how the accounts were obtained: "rpc" for a capture, "synthetic" for hand-built state
+    pub source: String,
+    // deployment that owns the pool, None for a program outside `WoofiDeployment::all`; only
+    // recorded for rpc captures, hand-built state does not come from any cluster
+    pub cluster: Option<Cluster>,
+    // slot of the clock account, i.e. the slot the accounts were read at; only recorded for rpc captures
+    pub slot: Option<u64>,
+    pub pool: AccountSnapshot,
+    pub accounts: Vec<AccountSnapshot>,
+    pub quotes: Vec<RecordedQuote>,
+}
+
+impl MarketSnapshot {
+    // Records the WooAmmPool, every account from `get_accounts_to_update` (clock included, each
+    // key once) and the results of `quote_params`. Cluster and slot are kept for rpc captures only.
+    pub fn capture(
+        label: &str,
+        source: &str,
+        keyed_account: &KeyedAccount,
+        account_map: &AccountMap,
+        quote_params: &[QuoteParams],
+    ) -> Result<Self> {
+        let clock = clock_from_account_map(account_map)?;
+        let slot = clock.slot;
+        let amm_context = AmmContext {
+            clock_ref: ClockRef::from(clock),
+        };
+
+        let mut woofi_swap = WoofiSwap::from_keyed_account(keyed_account, &amm_context)?;
+        let accounts = woofi_swap
+            .get_accounts_to_update()
+            .iter()
+            .map(|key| {
+                account_map
+                    .get(key)
+                    .map(|account| AccountSnapshot::new(key, account))
+                    .with_context(|| format!("Missing account {}", key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        woofi_swap.update(account_map)?;
+        let quotes = quote_params
+            .iter()
+            .map(|params| RecordedQuote::record(&woofi_swap, params))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(MarketSnapshot {
+            version: SNAPSHOT_VERSION,
+            label: label.into(),
+            source: source.into(),
+            cluster: woofi_swap.cluster.filter(|_| source == SOURCE_RPC),
+            slot: Some(slot).filter(|_| source == SOURCE_RPC),
+            pool: AccountSnapshot::new(&keyed_account.key, &keyed_account.account),
+            accounts,
+            quotes,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let snapshot: MarketSnapshot = serde_json::from_str(&json)?;
+        snapshot
+            .validate()
+            .with_context(|| format!("Invalid snapshot {}", path.display()))?;
+        Ok(snapshot)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(anyhow!("Unsupported snapshot version {}", self.version));
+        }
+
+        if self.source == SOURCE_RPC && self.slot.is_none() {
+            return Err(anyhow!("Snapshot from rpc without a slot"));
+        }
+        if self.source != SOURCE_RPC && (self.cluster.is_some() || self.slot.is_some()) {
+            return Err(anyhow!(
+                "Only rpc snapshots record a cluster and slot, not {}",
+                self.source
+            ));
+        }
+
+        let mut keys = HashSet::new();
+        if let Some(duplicate) = self
+            .accounts
+            .iter()
+            .find(|account| !keys.insert(&account.pubkey))
+        {
+            return Err(anyhow!("Duplicate account {}", duplicate.pubkey));
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn keyed_account(&self) -> Result<KeyedAccount> {
+        Ok(KeyedAccount {
+            key: self.pool.pubkey()?,
+            account: self.pool.account()?,
+            params: None,
+        })
+    }
+
+    pub fn account_map(&self) -> Result<AccountMap> {
+        let mut account_map = AccountMap::default();
+        for account in &self.accounts {
+            account_map.insert(account.pubkey()?, account.account()?);
+        }
+        Ok(account_map)
+    }
+
+    pub fn clock(&self) -> Result<Clock> {
+        clock_from_account_map(&self.account_map()?)
+    }
+
+    pub fn amm_context(&self) -> Result<AmmContext> {
+        Ok(AmmContext {
+            clock_ref: ClockRef::from(self.clock()?),
+        })
+    }
+
+    // from_keyed_account followed by update, ready to quote
+    pub fn woofi_swap(&self) -> Result<WoofiSwap> {
+        let mut woofi_swap =
+            WoofiSwap::from_keyed_account(&self.keyed_account()?, &self.amm_context()?)?;
+        woofi_swap.update(&self.account_map()?)?;
+        Ok(woofi_swap)
+    }
+
+    // Quotes the recorded requests again against the snapshot accounts
+    pub fn replay(&self) -> Result<Vec<RecordedQuote>> {
+        let woofi_swap = self.woofi_swap()?;
+        self.quotes
+            .iter()
+            .map(|recorded| RecordedQuote::record(&woofi_swap, &recorded.quote_params()?))
+            .collect()
+    }
+}
+
+// Short name for a mint in snapshot labels and file names
+pub fn mint_label(mint: &Pubkey) -> String {
+    match *mint {
+        SOL => "SOL".into(),
+        USDC => "USDC".into(),
+        _ => mint.to_string()[..8].into(),
+    }
+}
+
+fn clock_from_account_map(account_map: &AccountMap) -> Result<Clock> {
+    let clock_account = account_map
+        .get(&sysvar::clock::ID)
+        .context("Missing clock account")?;
+    bincode::deserialize(&clock_account.data).context("Failed to deserialize sysvar::clock::ID")
+}
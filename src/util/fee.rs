@@ -0,0 +1,8 @@
+use rust_decimal::Decimal;
+
+use crate::constants::ONE_E5_U128;
+
+// WooPool fee_rate (0.1 bps units, 1e5 = 100%) as a fraction, e.g. 25 -> 0.00025
+pub fn fee_rate_to_pct(fee_rate: u16) -> Decimal {
+    Decimal::from(fee_rate) / Decimal::from(ONE_E5_U128)
+}
@@ -4,10 +4,16 @@ use anchor_lang::{
     prelude::{borsh, AnchorDeserialize, AnchorSerialize},
     Key,
 };
-use anyhow::{Context, Result};
+use anyhow::Result;
 use jupiter_amm_interface::ClockRef;
+use serde::{Deserialize, Serialize};
 
-use crate::{constants::*, errors::ErrorCode, state::wooracle::*};
+use crate::{
+    constants::*,
+    errors::{ErrorCode, OracleFailure, QuoteError},
+    state::wooracle::*,
+    util::checked_mul_div,
+};
 
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
@@ -25,46 +31,244 @@ pub struct GetStateResult {
     pub feasible_out: bool,
 }
 
-pub fn get_price_impl<'info>(
+// Checks applied to the Pyth prices a wooracle is compared against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OracleGuard {
+    // max pyth conf / price in 1e18 units (1e16 = 1%); the wooracle's bound when not set, since a
+    // confidence interval wider than the bound leaves the bound check nothing to compare against
+    pub max_conf_ratio: Option<u128>,
+    // seconds; caps the wooracle stale_duration and pyth maximum_age when set
+    pub max_staleness: Option<u64>,
+}
+
+impl OracleGuard {
+    pub fn stale_duration(&self, stale_duration: i64) -> i64 {
+        self.max_staleness.map_or(stale_duration, |max| {
+            stale_duration.min(i64::try_from(max).unwrap_or(i64::MAX))
+        })
+    }
+
+    pub fn maximum_age(&self, maximum_age: u64) -> u64 {
+        self.max_staleness
+            .map_or(maximum_age, |max| maximum_age.min(max))
+    }
+
+    pub fn max_conf_ratio(&self, bound: u64) -> u128 {
+        self.max_conf_ratio.unwrap_or(bound as u128)
+    }
+
+    pub fn conf_within_limit(&self, price: i64, conf: u64, bound: u64) -> bool {
+        if price <= 0 {
+            return false;
+        }
+        let conf_ratio = (conf as u128) * ONE_E18_U128 / (price as u128);
+        conf_ratio <= self.max_conf_ratio(bound)
+    }
+}
+
+// Base/quote price from two Pyth feeds, in the wooracle's `price_decimals`:
+// (base_price * 10^base_expo) / (quote_price * 10^quote_expo) * 10^price_decimals, rounded down.
+// None for non-positive prices or when the result does not fit in u128.
+pub fn normalize_pyth_price(
+    base_price: i64,
+    base_expo: i32,
+    quote_price: i64,
+    quote_expo: i32,
+    price_decimals: u8,
+) -> Option<u128> {
+    if base_price <= 0 || quote_price <= 0 {
+        return None;
+    }
+
+    let base_price = base_price as u128;
+    let quote_price = quote_price as u128;
+    let expo = base_expo as i64 - quote_expo as i64 + price_decimals as i64;
+
+    if expo >= 0 {
+        let scale = 10_u128.checked_pow(u32::try_from(expo).ok()?)?;
+        checked_mul_div(base_price, scale, quote_price).ok()
+    } else {
+        // past 10^38 the divisor exceeds any i64 price, so the result is 0
+        let scale = match 10_u128.checked_pow(u32::try_from(-expo).ok()?) {
+            Some(scale) => scale,
+            None => return Some(0),
+        };
+        Some(
+            quote_price
+                .checked_mul(scale)
+                .map_or(0, |divisor| base_price / divisor),
+        )
+    }
+}
+
+// Everything the feasibility decision for one wooracle is based on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OracleHealth {
+    pub wo_price: u128,
+    // pyth base / quote price in the wooracle price decimals, 0 if unusable
+    pub clo_price: u128,
+    pub lower_bound: u128,
+    pub upper_bound: u128,
+    // unix timestamp of the last wooracle update
+    pub updated_at: i64,
+    // seconds since the wooracle was last updated
+    pub wo_age: i64,
+    pub stale_duration: i64,
+    // seconds since each pyth update was published
+    pub pyth_age: i64,
+    pub quote_pyth_age: i64,
+    pub maximum_age: u64,
+    // both pyth updates are fully verified, match the wooracle feeds and are within maximum_age
+    pub pyth_usable: bool,
+    pub conf_within_limit: bool,
+    pub range_min: u128,
+    pub range_max: u128,
+    pub feasible: bool,
+}
+
+impl OracleHealth {
+    pub fn is_fresh(&self) -> bool {
+        self.wo_age <= self.stale_duration
+    }
+
+    pub fn price_in_bound(&self) -> bool {
+        self.clo_price != 0
+            && self.lower_bound <= self.wo_price
+            && self.wo_price <= self.upper_bound
+    }
+
+    pub fn price_in_range(&self) -> bool {
+        self.range_min <= self.wo_price && self.wo_price <= self.range_max
+    }
+
+    // negative once the price has dropped below range_min
+    pub fn range_min_margin(&self) -> i128 {
+        signed(self.wo_price).saturating_sub(signed(self.range_min))
+    }
+
+    // negative once the price has risen above range_max
+    pub fn range_max_margin(&self) -> i128 {
+        signed(self.range_max).saturating_sub(signed(self.wo_price))
+    }
+
+    // None when feasible
+    pub fn infeasible_reason(&self) -> Option<OracleFailure> {
+        if !self.pyth_usable || self.clo_price == 0 {
+            Some(OracleFailure::PythUnusable)
+        } else if !self.conf_within_limit {
+            Some(OracleFailure::ConfidenceTooWide)
+        } else if !self.is_fresh() {
+            Some(OracleFailure::Stale)
+        } else if !self.price_in_bound() {
+            Some(OracleFailure::OutOfBound)
+        } else if !self.price_in_range() {
+            Some(OracleFailure::OutOfRange)
+        } else {
+            None
+        }
+    }
+
+    // What the program's get_price returns for `oracle` at this health: an error where it would
+    // revert, otherwise the wooracle price when feasible and 0 when not
+    pub fn price(&self, oracle: &Wooracle) -> Result<GetPriceResult> {
+        if !self.pyth_usable {
+            return Err(QuoteError::OracleNotFeasible {
+                mint: oracle.token_mint,
+                reason: Some(OracleFailure::PythUnusable),
+            }
+            .into());
+        }
+
+        if self.passes_bound_check() && !self.price_in_range() {
+            return Err(QuoteError::PriceOutOfRange {
+                price: self.wo_price,
+                range_min: self.range_min,
+                range_max: self.range_max,
+            }
+            .into());
+        }
+
+        Ok(GetPriceResult {
+            price_out: if self.feasible { self.wo_price } else { 0 },
+            feasible_out: self.feasible,
+        })
+    }
+
+    // get_state for the wooracle this health was computed from
+    pub fn state(&self, oracle: &Wooracle) -> Result<GetStateResult> {
+        let price_result = self.price(oracle)?;
+        Ok(GetStateResult {
+            price_out: price_result.price_out,
+            spread: oracle.spread,
+            coeff: oracle.coeff,
+            feasible_out: price_result.feasible_out,
+        })
+    }
+
+    // The same checks with the wooracle at `wo_price`, as after the program posts a new price at
+    // unix timestamp `now`: posting also sets updated_at, so the wooracle is fresh again
+    pub fn with_wo_price(&self, wo_price: u128, now: i64) -> OracleHealth {
+        let mut health = OracleHealth {
+            wo_price,
+            updated_at: now,
+            wo_age: 0,
+            ..*self
+        };
+        health.feasible = health.passes_bound_check() && health.price_in_range();
+        health
+    }
+
+    // every check except range_min/range_max passes
+    fn passes_bound_check(&self) -> bool {
+        self.pyth_usable && self.conf_within_limit && self.is_fresh() && self.price_in_bound()
+    }
+}
+
+fn signed(value: u128) -> i128 {
+    i128::try_from(value).unwrap_or(i128::MAX)
+}
+
+pub fn get_oracle_health(
     clock: &ClockRef,
     oracle: &Wooracle,
     price_update: &PriceUpdateV2,
     quote_price_update: &PriceUpdateV2,
-) -> Result<GetPriceResult> {
+    guard: &OracleGuard,
+) -> Result<OracleHealth> {
     let now = clock.unix_timestamp.load(Ordering::Relaxed);
+    let maximum_age = guard.maximum_age(oracle.maximum_age);
 
-    let pyth_result = price_update
-        .get_price_no_older_than(
-            now,
-            oracle.maximum_age,
-            &oracle.feed_account.key().to_bytes(),
-        )
-        .ok()
-        .context("pyth price update failed")?;
-
-    let quote_price_result = quote_price_update
-        .get_price_no_older_than(
-            now,
-            oracle.maximum_age,
-            &oracle.quote_feed_account.key().to_bytes(),
-        )
-        .ok()
-        .context("pyth price update failed")?;
+    let pyth_usable = price_update
+        .get_price_no_older_than(now, maximum_age, &oracle.feed_account.key().to_bytes())
+        .is_ok()
+        && quote_price_update
+            .get_price_no_older_than(
+                now,
+                maximum_age,
+                &oracle.quote_feed_account.key().to_bytes(),
+            )
+            .is_ok();
 
-    let base_price = pyth_result.price as u128;
-    let quote_price = quote_price_result.price as u128;
-    let quote_decimal = quote_price_result.exponent.unsigned_abs();
+    // read straight from the messages so stale updates are still reported
+    let base_message = &price_update.price_message;
+    let quote_message = &quote_price_update.price_message;
 
-    let clo_price = base_price
-        .checked_mul(10_u128.pow(quote_decimal))
-        .and_then(|v| v.checked_div(quote_price))
-        .ok_or(ErrorCode::MathOverflow)?;
+    // an unusable reference price leaves clo_price at 0, which marks the oracle infeasible
+    let clo_price = normalize_pyth_price(
+        base_message.price,
+        base_message.exponent,
+        quote_message.price,
+        quote_message.exponent,
+        oracle.price_decimals,
+    )
+    .unwrap_or(0);
 
-    let wo_price = oracle.price;
-    let wo_timestamp = oracle.updated_at;
-    let bound = oracle.bound as u128;
+    // a wide confidence interval means pyth is not a usable reference for the bound check
+    let conf_within_limit =
+        guard.conf_within_limit(base_message.price, base_message.conf, oracle.bound)
+            && guard.conf_within_limit(quote_message.price, quote_message.conf, oracle.bound);
 
-    let wo_feasible = clo_price != 0 && now <= (wo_timestamp + oracle.stale_duration);
+    let bound = oracle.bound as u128;
 
     // Safe math for price bound checks
     let lower_bound = ONE_E18_U128
@@ -79,31 +283,36 @@ pub fn get_price_impl<'info>(
         .and_then(|v| v.checked_div(ONE_E18_U128))
         .ok_or(ErrorCode::MathOverflow)?;
 
-    let wo_price_in_bound = clo_price != 0 && (lower_bound <= wo_price && wo_price <= upper_bound);
+    let mut health = OracleHealth {
+        wo_price: oracle.price,
+        clo_price,
+        lower_bound,
+        upper_bound,
+        updated_at: oracle.updated_at,
+        wo_age: now.saturating_sub(oracle.updated_at),
+        stale_duration: guard.stale_duration(oracle.stale_duration),
+        pyth_age: now.saturating_sub(base_message.publish_time),
+        quote_pyth_age: now.saturating_sub(quote_message.publish_time),
+        maximum_age,
+        pyth_usable,
+        conf_within_limit,
+        range_min: oracle.range_min,
+        range_max: oracle.range_max,
+        feasible: false,
+    };
+    health.feasible = health.passes_bound_check() && health.price_in_range();
 
-    let price_out: u128;
-    let feasible_out: bool;
-    if wo_feasible && wo_price_in_bound {
-        price_out = wo_price;
-        feasible_out = true;
-    } else {
-        price_out = 0;
-        feasible_out = false;
-    }
-
-    if feasible_out {
-        if price_out < oracle.range_min {
-            return Err(ErrorCode::WooOraclePriceRangeMin.into());
-        }
-        if price_out > oracle.range_max {
-            return Err(ErrorCode::WooOraclePriceRangeMax.into());
-        }
-    }
+    Ok(health)
+}
 
-    Ok(GetPriceResult {
-        price_out,
-        feasible_out,
-    })
+pub fn get_price_impl<'info>(
+    clock: &ClockRef,
+    oracle: &Wooracle,
+    price_update: &PriceUpdateV2,
+    quote_price_update: &PriceUpdateV2,
+    guard: &OracleGuard,
+) -> Result<GetPriceResult> {
+    get_oracle_health(clock, oracle, price_update, quote_price_update, guard)?.price(oracle)
 }
 
 pub fn get_state_impl<'info>(
@@ -111,12 +320,7 @@ pub fn get_state_impl<'info>(
     oracle: &Wooracle,
     price_update: &PriceUpdateV2,
     quote_price_update: &PriceUpdateV2,
+    guard: &OracleGuard,
 ) -> Result<GetStateResult> {
-    let price_result = get_price_impl(clock, oracle, price_update, quote_price_update)?;
-    Ok(GetStateResult {
-        price_out: price_result.price_out,
-        spread: oracle.spread,
-        coeff: oracle.coeff,
-        feasible_out: price_result.feasible_out,
-    })
+    get_oracle_health(clock, oracle, price_update, quote_price_update, guard)?.state(oracle)
 }
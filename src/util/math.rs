@@ -1,11 +1,38 @@
-use crate::errors::ErrorCode;
+use crate::errors::{ErrorCode, QuoteError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+// n0 * n1 / d with a 256-bit intermediate product, so it only fails when the quotient itself
+// does not fit in u128
+pub fn mul_div(n0: u128, n1: u128, d: u128, rounding: Rounding) -> Result<u128, ErrorCode> {
+    if d == 0 {
+        return Err(ErrorCode::DivideByZero);
+    }
+
+    let (hi, lo) = full_mul(n0, n1);
+    let (quotient, remainder) = div_rem_256(hi, lo, d).ok_or(ErrorCode::MulDivOverflow)?;
+
+    match rounding {
+        Rounding::Up if remainder > 0 => quotient.checked_add(1).ok_or(ErrorCode::MulDivOverflow),
+        _ => Ok(quotient),
+    }
+}
+
+// Token amounts are u64 on chain; anything larger is an error rather than a truncated amount
+pub fn checked_to_u64(amount: u128) -> Result<u64, QuoteError> {
+    u64::try_from(amount).map_err(|_| QuoteError::AmountOverflow { amount })
+}
 
 pub fn checked_mul_div(n0: u128, n1: u128, d: u128) -> Result<u128, ErrorCode> {
-    checked_mul_div_round_up_if(n0, n1, d, false)
+    mul_div(n0, n1, d, Rounding::Down)
 }
 
 pub fn checked_mul_div_round_up(n0: u128, n1: u128, d: u128) -> Result<u128, ErrorCode> {
-    checked_mul_div_round_up_if(n0, n1, d, true)
+    mul_div(n0, n1, d, Rounding::Up)
 }
 
 pub fn checked_mul_div_round_up_if(
@@ -14,12 +41,60 @@ pub fn checked_mul_div_round_up_if(
     d: u128,
     round_up: bool,
 ) -> Result<u128, ErrorCode> {
-    if d == 0 {
-        return Err(ErrorCode::DivideByZero);
+    mul_div(
+        n0,
+        n1,
+        d,
+        if round_up {
+            Rounding::Up
+        } else {
+            Rounding::Down
+        },
+    )
+}
+
+const LOW_64: u128 = u64::MAX as u128;
+
+// (hi, lo) halves of the 256-bit product
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    let (a1, a0) = (a >> 64, a & LOW_64);
+    let (b1, b0) = (b >> 64, b & LOW_64);
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+
+    // at most 3 * (2^64 - 1), no overflow
+    let mid = (p00 >> 64) + (p01 & LOW_64) + (p10 & LOW_64);
+
+    let lo = (p00 & LOW_64) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+// (hi * 2^128 + lo) / d and the remainder, None when the quotient does not fit in u128
+fn div_rem_256(hi: u128, lo: u128, d: u128) -> Option<(u128, u128)> {
+    if hi >= d {
+        return None;
+    }
+    if hi == 0 {
+        return Some((lo / d, lo % d));
     }
 
-    let p = n0.checked_mul(n1).ok_or(ErrorCode::MulDivOverflow)?;
-    let n = p / d;
+    // shift-subtract long division; remainder < d holds before every step
+    let mut remainder = hi;
+    let mut quotient: u128 = 0;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        // with the carry the true remainder is 2^128 + remainder, which is >= d
+        if carry == 1 || remainder >= d {
+            remainder = remainder.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
 
-    Ok(if round_up && p % d > 0 { n + 1 } else { n })
+    Some((quotient, remainder))
 }
@@ -1,13 +1,17 @@
-pub mod helpers;
 pub mod decimals;
+pub mod fee;
 pub mod get_price;
-pub mod swap_math;
+pub mod helpers;
 pub mod math;
+pub mod price_impact;
+pub mod swap_math;
 pub mod token;
 
-pub use helpers::*;
 pub use decimals::*;
+pub use fee::*;
 pub use get_price::*;
-pub use swap_math::*;
+pub use helpers::*;
 pub use math::*;
+pub use price_impact::*;
+pub use swap_math::*;
 pub use token::*;
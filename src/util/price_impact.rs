@@ -0,0 +1,92 @@
+use anyhow::Result;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::*, errors::ErrorCode, util::Decimals};
+
+// Prices in output tokens per input token (not native units)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceImpact {
+    // zero-size price: oracle prices with spread and swap fee applied
+    pub marginal_price: Decimal,
+    // out_amount / in_amount
+    pub execution_price: Decimal,
+    // zero-size price once the swap has moved the wooracle prices
+    pub post_trade_price: Decimal,
+    // shortfall of execution_price against marginal_price
+    pub price_impact_bps: Decimal,
+}
+
+impl PriceImpact {
+    pub fn new(
+        marginal_price: Decimal,
+        execution_price: Decimal,
+        post_trade_price: Decimal,
+    ) -> Result<Self> {
+        let shortfall = marginal_price
+            .checked_sub(execution_price)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let price_impact_bps = checked_decimal_div(
+            checked_decimal_mul(shortfall, Decimal::from(10_000))?,
+            marginal_price,
+        )?;
+
+        Ok(PriceImpact {
+            marginal_price,
+            execution_price,
+            post_trade_price,
+            price_impact_bps,
+        })
+    }
+}
+
+pub fn checked_decimal_mul(a: Decimal, b: Decimal) -> Result<Decimal> {
+    Ok(a.checked_mul(b).ok_or(ErrorCode::MathOverflow)?)
+}
+
+pub fn checked_decimal_div(a: Decimal, b: Decimal) -> Result<Decimal> {
+    if b.is_zero() {
+        return Err(ErrorCode::DivideByZero.into());
+    }
+    Ok(a.checked_div(b).ok_or(ErrorCode::MathOverflow)?)
+}
+
+fn to_decimal(value: u128) -> Result<Decimal> {
+    Ok(Decimal::from_u128(value).ok_or(ErrorCode::NumberCastError)?)
+}
+
+// 1 - spread, spread in 1e18 units
+fn spread_factor(spread: u64) -> Result<Decimal> {
+    Ok(Decimal::ONE - checked_decimal_div(Decimal::from(spread), to_decimal(ONE_E18_U128)?)?)
+}
+
+// Wooracle price as quote tokens per base token
+fn oracle_price(price: u128, decimals: &Decimals) -> Result<Decimal> {
+    checked_decimal_div(to_decimal(price)?, Decimal::from(decimals.price_dec))
+}
+
+// Quote tokens received per base token sold at zero size, before the swap fee
+pub fn marginal_price_sell_base(price: u128, spread: u64, decimals: &Decimals) -> Result<Decimal> {
+    checked_decimal_mul(oracle_price(price, decimals)?, spread_factor(spread)?)
+}
+
+// Base tokens received per quote token sold at zero size, before the swap fee
+pub fn marginal_price_sell_quote(price: u128, spread: u64, decimals: &Decimals) -> Result<Decimal> {
+    checked_decimal_div(spread_factor(spread)?, oracle_price(price, decimals)?)
+}
+
+// Output tokens per input token, from native amounts and each token's `base_dec`
+pub fn execution_price(
+    in_amount: u128,
+    decimals_in: &Decimals,
+    out_amount: u128,
+    decimals_out: &Decimals,
+) -> Result<Decimal> {
+    let in_tokens =
+        checked_decimal_div(to_decimal(in_amount)?, Decimal::from(decimals_in.base_dec))?;
+    let out_tokens = checked_decimal_div(
+        to_decimal(out_amount)?,
+        Decimal::from(decimals_out.base_dec),
+    )?;
+    checked_decimal_div(out_tokens, in_tokens)
+}
@@ -1,6 +1,34 @@
 use anyhow::Result;
 
-use crate::{constants::*, errors::ErrorCode, state::*, util::*};
+use crate::{
+    constants::*,
+    errors::{ErrorCode, QuoteError},
+    state::*,
+    util::*,
+};
+
+// Rounding contract: every division rounds down, in the association order below. The Solidity
+// formulas in the comments are the WooPPV2 originals, which associate differently in places.
+// These steps are meant to follow the on-chain program so quotes match to the lamport, but that is
+// only established once test_recorded_swaps_match_to_the_lamport runs against swaps recorded on
+// mainnet; until then the tests only check the arithmetic against these same steps.
+//
+// sell base (base_amount -> quote_amount):
+//   a        = floor(base_amount * price / price_dec)
+//   gamma    = floor(a * coeff / base_dec)                    Solidity: base * price * coeff / price_dec / base_dec
+//   c        = floor(a * (1e18 - gamma - spread) / 1e18)
+//   quote    = floor(c * quote_dec / base_dec)                Solidity: divides by base_dec last, after 1e18
+//   newPrice = floor((1e18 - gamma) * price / 1e18)
+//
+// sell quote (quote_amount -> base_amount):
+//   gamma    = floor(quote_amount * coeff / quote_dec)
+//   b        = floor(quote_amount * base_dec * price_dec / price)
+//   d        = floor(b * (1e18 - gamma - spread) / 1e18)
+//   base     = floor(d / quote_dec)
+//   newPrice = floor(1e18 * price / (1e18 - gamma))
+//
+// The swap fee on the quote leg rounds up (see `WoofiSwap::compute_swap`), so both rounding
+// directions favour the pool.
 
 pub fn calc_quote_amount_sell_base(
     base_amount: u128,
@@ -8,34 +36,44 @@ pub fn calc_quote_amount_sell_base(
     decimals: &Decimals,
     state: &GetStateResult,
 ) -> Result<(u128, u128)> {
-    if !state.feasible_out {
-        return Err(ErrorCode::WooOracleNotFeasible.into());
-    }
-    if state.price_out <= 0 {
-        return Err(ErrorCode::WooOraclePriceNotValid.into());
+    if !state.feasible_out || state.price_out == 0 {
+        return Err(QuoteError::OracleNotFeasible {
+            mint: woopool.token_mint,
+            reason: None,
+        }
+        .into());
     }
 
     //let notionalSwap : u128 = (base_amount * state.price_out * decimals.quote_dec) / decimals.base_dec / decimals.price_dec;
-    let notion_calc_a: u128 =
-        checked_mul_div(base_amount, state.price_out, decimals.price_dec as u128)?;
-    let notional_swap: u128 = checked_mul_div(
+    let notion_calc_a: u128 = mul_div(
+        base_amount,
+        state.price_out,
+        decimals.price_dec as u128,
+        Rounding::Down,
+    )?;
+    let notional_swap: u128 = mul_div(
         notion_calc_a,
         decimals.quote_dec as u128,
         decimals.base_dec as u128,
+        Rounding::Down,
     )?;
 
     if notional_swap > woopool.max_notional_swap {
-        return Err(ErrorCode::WooPoolExceedMaxNotionalValue.into());
+        return Err(QuoteError::MaxNotionalExceeded {
+            notional: notional_swap,
+            max_notional_swap: woopool.max_notional_swap,
+        }
+        .into());
     }
 
-    // gamma = k * price * base_amount; and decimal 18
-    let gamma_calc_a: u128 =
-        checked_mul_div(base_amount, state.price_out, decimals.price_dec as u128)?;
-    let gamma: u128 =
-        checked_mul_div(gamma_calc_a, state.coeff as u128, decimals.base_dec as u128)?;
-    
+    let gamma = gamma_sell_base(base_amount, decimals, state)?;
+
     if gamma > woopool.max_gamma {
-        return Err(ErrorCode::WooPoolExceedMaxGamma.into());
+        return Err(QuoteError::GammaExceeded {
+            gamma,
+            max_gamma: woopool.max_gamma,
+        }
+        .into());
     }
 
     // Formula: quoteAmount = baseAmount * oracle.price * (1 - oracle.k * baseAmount * oracle.price - oracle.spread)
@@ -55,22 +93,29 @@ pub fn calc_quote_amount_sell_base(
     // quoteAmount = ((a * decs.quoteDec) * b) / 1e18 / decs.baseDec;
     //             = ((a * b) / 1e18) * decs.quoteDec / decs.baseDec
 
-    let calc_a: u128 = checked_mul_div(base_amount, state.price_out, decimals.price_dec as u128)?;
-    let calc_b: u128 = ONE_E18_U128
-        .checked_sub(gamma).ok_or(ErrorCode::MathOverflow)?
-        .checked_sub(state.spread as u128).ok_or(ErrorCode::MathOverflow)?;
-    let calc_c = checked_mul_div(calc_a, calc_b, ONE_E18_U128)?;
-    let quote_amount = checked_mul_div(
+    let calc_a: u128 = mul_div(
+        base_amount,
+        state.price_out,
+        decimals.price_dec as u128,
+        Rounding::Down,
+    )?;
+    let calc_b: u128 = one_minus_gamma_spread(gamma, state.spread)?;
+    let calc_c = mul_div(calc_a, calc_b, ONE_E18_U128, Rounding::Down)?;
+    let quote_amount = mul_div(
         calc_c,
         decimals.quote_dec as u128,
         decimals.base_dec as u128,
+        Rounding::Down,
     )?;
 
     // newPrice = oracle.price * (1 - k * oracle.price * baseAmount)
-    let new_price: u128 = checked_mul_div(
-        ONE_E18_U128.checked_sub(gamma).ok_or(ErrorCode::MathOverflow)?,
+    let new_price: u128 = mul_div(
+        ONE_E18_U128
+            .checked_sub(gamma)
+            .ok_or(ErrorCode::MathOverflow)?,
         state.price_out,
         ONE_E18_U128,
+        Rounding::Down,
     )?;
 
     Ok((quote_amount, new_price))
@@ -82,45 +127,240 @@ pub fn calc_base_amount_sell_quote(
     decimals: &Decimals,
     state: &GetStateResult,
 ) -> Result<(u128, u128)> {
-    if !state.feasible_out {
-        return Err(ErrorCode::WooOracleNotFeasible.into());
-    }
-    if state.price_out <= 0 {
-        return Err(ErrorCode::WooOraclePriceNotValid.into());
+    if !state.feasible_out || state.price_out == 0 {
+        return Err(QuoteError::OracleNotFeasible {
+            mint: woopool.token_mint,
+            reason: None,
+        }
+        .into());
     }
 
     if quote_amount > woopool.max_notional_swap {
-        return Err(ErrorCode::WooPoolExceedMaxNotionalValue.into());
+        return Err(QuoteError::MaxNotionalExceeded {
+            notional: quote_amount,
+            max_notional_swap: woopool.max_notional_swap,
+        }
+        .into());
     }
 
-    // gamma = k * quote_amount; and decimal 18
-    let gamma: u128 = checked_mul_div(
-        quote_amount,
-        state.coeff as u128,
-        decimals.quote_dec as u128,
-    )?;
+    let gamma = gamma_sell_quote(quote_amount, decimals, state)?;
 
     if gamma > woopool.max_gamma {
-        return Err(ErrorCode::WooPoolExceedMaxGamma.into());
+        return Err(QuoteError::GammaExceeded {
+            gamma,
+            max_gamma: woopool.max_gamma,
+        }
+        .into());
     }
 
     // Formula: baseAmount = quoteAmount / oracle.price * (1 - oracle.k * quoteAmount - oracle.spread)
-    let calc_a: u128 = quote_amount
-        .checked_mul(decimals.base_dec as u128)
+    // base_dec * price_dec is a product of two u64s, and mul_div keeps quote_amount times it in a
+    // 256-bit intermediate, so large quote amounts into 18 decimal tokens do not overflow
+    let calc_a: u128 = decimals.base_dec as u128 * decimals.price_dec as u128;
+    let calc_b: u128 = mul_div(quote_amount, calc_a, state.price_out, Rounding::Down)?;
+    let calc_c: u128 = one_minus_gamma_spread(gamma, state.spread)?;
+    let calc_d: u128 = mul_div(calc_b, calc_c, ONE_E18_U128, Rounding::Down)?;
+    let base_amount = calc_d
+        .checked_div(decimals.quote_dec as u128)
         .ok_or(ErrorCode::MathOverflow)?;
-    let calc_b: u128 = checked_mul_div(calc_a, decimals.price_dec as u128, state.price_out)?;
-    let calc_c: u128 = ONE_E18_U128
-        .checked_sub(gamma).ok_or(ErrorCode::MathOverflow)?
-        .checked_sub(state.spread as u128).ok_or(ErrorCode::MathOverflow)?;
-    let calc_d: u128 = checked_mul_div(calc_b, calc_c, ONE_E18_U128)?;
-    let base_amount = calc_d.checked_div(decimals.quote_dec as u128).ok_or(ErrorCode::MathOverflow)?;
 
     // new_price = oracle.price / (1 - k * quoteAmount)
-    let new_price: u128 = checked_mul_div(
+    let new_price: u128 = mul_div(
         ONE_E18_U128,
         state.price_out,
-        ONE_E18_U128.checked_sub(gamma).ok_or(ErrorCode::MathOverflow)?,
+        ONE_E18_U128
+            .checked_sub(gamma)
+            .ok_or(ErrorCode::MathOverflow)?,
+        Rounding::Down,
     )?;
 
     Ok((base_amount, new_price))
 }
+
+// gamma = k * price * base_amount; and decimal 18
+fn gamma_sell_base(base_amount: u128, decimals: &Decimals, state: &GetStateResult) -> Result<u128> {
+    let gamma_calc_a: u128 = mul_div(
+        base_amount,
+        state.price_out,
+        decimals.price_dec as u128,
+        Rounding::Down,
+    )?;
+    mul_div(
+        gamma_calc_a,
+        state.coeff as u128,
+        decimals.base_dec as u128,
+        Rounding::Down,
+    )
+}
+
+// gamma = k * quote_amount; and decimal 18
+fn gamma_sell_quote(
+    quote_amount: u128,
+    decimals: &Decimals,
+    state: &GetStateResult,
+) -> Result<u128> {
+    mul_div(
+        quote_amount,
+        state.coeff as u128,
+        decimals.quote_dec as u128,
+        Rounding::Down,
+    )
+}
+
+// Both sells pay out x * (1e18 - gamma - spread) for an input x with gamma proportional to x, so
+// the output is concave in the input and stops rising once 2 * gamma + spread passes 1e18
+fn output_rising(gamma: u128, spread: u64) -> bool {
+    gamma.saturating_mul(2).saturating_add(spread as u128) <= ONE_E18_U128
+}
+
+// 1e18 - gamma - spread, which the program requires to be non-negative
+fn one_minus_gamma_spread(gamma: u128, spread: u64) -> Result<u128> {
+    Ok(ONE_E18_U128
+        .checked_sub(gamma)
+        .and_then(|v| v.checked_sub(spread as u128))
+        .ok_or(QuoteError::SpreadPlusGammaExceeded { gamma, spread })?)
+}
+
+// Smallest base amount whose `calc_quote_amount_sell_base` output is at least `quote_amount`.
+pub fn calc_base_amount_buy_quote(
+    quote_amount: u128,
+    woopool: &WooPool,
+    decimals: &Decimals,
+    state: &GetStateResult,
+) -> Result<u128> {
+    // Start from the gamma-free inverse: base = quote * baseDec * priceDec / (quoteDec * price * (1 - spread))
+    let estimate = mul_div(
+        quote_amount,
+        decimals.base_dec as u128,
+        decimals.quote_dec as u128,
+        Rounding::Down,
+    )
+    .and_then(|v| {
+        mul_div(
+            v,
+            decimals.price_dec as u128,
+            state.price_out,
+            Rounding::Down,
+        )
+    })
+    .and_then(|v| {
+        mul_div(
+            v,
+            ONE_E18_U128,
+            ONE_E18_U128.saturating_sub(state.spread as u128),
+            Rounding::Down,
+        )
+    })
+    .unwrap_or(1);
+
+    min_amount_in(quote_amount, estimate, |base_amount| {
+        let (amount, _) = calc_quote_amount_sell_base(base_amount, woopool, decimals, state)?;
+        let gamma = gamma_sell_base(base_amount, decimals, state)?;
+        Ok((amount, output_rising(gamma, state.spread)))
+    })
+}
+
+// Smallest quote amount whose `calc_base_amount_sell_quote` output is at least `base_amount`.
+pub fn calc_quote_amount_buy_base(
+    base_amount: u128,
+    woopool: &WooPool,
+    decimals: &Decimals,
+    state: &GetStateResult,
+) -> Result<u128> {
+    // Start from the gamma-free inverse: quote = base * price * quoteDec / (priceDec * baseDec * (1 - spread))
+    let estimate = mul_div(
+        base_amount,
+        state.price_out,
+        decimals.price_dec as u128,
+        Rounding::Down,
+    )
+    .and_then(|v| {
+        mul_div(
+            v,
+            decimals.quote_dec as u128,
+            decimals.base_dec as u128,
+            Rounding::Down,
+        )
+    })
+    .and_then(|v| {
+        mul_div(
+            v,
+            ONE_E18_U128,
+            ONE_E18_U128.saturating_sub(state.spread as u128),
+            Rounding::Down,
+        )
+    })
+    .unwrap_or(1);
+
+    min_amount_in(base_amount, estimate, |quote_amount| {
+        let (amount, _) = calc_base_amount_sell_quote(quote_amount, woopool, decimals, state)?;
+        let gamma = gamma_sell_quote(quote_amount, decimals, state)?;
+        Ok((amount, output_rising(gamma, state.spread)))
+    })
+}
+
+// Finds the smallest input for which `forward` reaches `target`. `forward` returns the output and
+// whether it is still rising at that input (see `output_rising`). The errors it runs into (max
+// notional, max gamma, spread plus gamma, overflow) hold for every larger input once hit, so the
+// inputs that succeed with a rising output form a range starting at 0 on which the output is
+// non-decreasing. The search stays inside that range: a target beyond it returns the error of
+// the first input past it, or OutputUnreachable when the output peaks below the target.
+fn min_amount_in<F>(target: u128, estimate: u128, forward: F) -> Result<u128>
+where
+    F: Fn(u128) -> Result<(u128, bool)>,
+{
+    if target == 0 {
+        return Ok(0);
+    }
+
+    // lo is usable and below the target, hi reaches it
+    let mut lo: u128 = 0;
+    let mut hi: u128 = estimate.max(1);
+    loop {
+        match forward(hi) {
+            Ok((out, true)) if out >= target => break,
+            Ok((_, true)) => {
+                lo = hi;
+                hi = hi.checked_mul(2).ok_or(ErrorCode::MathOverflow)?;
+            }
+            _ => {
+                let limit = max_usable_amount(lo, hi, &forward);
+                let (max_out, _) = forward(limit)?;
+                if max_out < target {
+                    forward(limit + 1)?;
+                    return Err(QuoteError::OutputUnreachable { target, max_out }.into());
+                }
+                hi = limit;
+                break;
+            }
+        }
+    }
+
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        let (out, _) = forward(mid)?;
+        if out >= target {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    Ok(hi)
+}
+
+// Largest input in [lo, hi) for which `forward` succeeds with a rising output, where hi does not
+fn max_usable_amount<F>(mut lo: u128, mut hi: u128, forward: &F) -> u128
+where
+    F: Fn(u128) -> Result<(u128, bool)>,
+{
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if matches!(forward(mid), Ok((_, true))) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
@@ -5,27 +5,35 @@ use jupiter_amm_interface::{
 
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{clock::Clock, sysvar};
-use woofi_jupiter::{util::{get_wooammpool_address, get_wooconfig_address, SOL, USDC}, WoofiSwap};
+use woofi_jupiter::{
+    cluster::Cluster,
+    util::{get_wooammpool_address, SOL},
+    WoofiSwap,
+};
 
 #[tokio::test]
 // TODO replace with local accounts
 async fn test_jupiter_quote() -> Result<(), Error> {
-    // devnet
-    //let client = RpcClient::new("https://api.devnet.solana.com".to_string());
-    let client = RpcClient::new("https://api.mainnet-beta.solana.com".to_string());
+    // WOOFI_CLUSTER=devnet to quote the devnet market
+    let cluster: Cluster = std::env::var("WOOFI_CLUSTER")
+        .unwrap_or("mainnet-beta".into())
+        .parse()?;
+    let deployment = cluster.deployment();
+    let client = RpcClient::new(format!("https://api.{}.solana.com", cluster));
+
+    let program_id = deployment.program_id;
+    let usdc = deployment.usdc_mint;
 
-    let program_id = woofi_jupiter::id();
-    
     let token_mint_a = SOL;
-    let token_mint_b = USDC;
+    let token_mint_b = usdc;
 
-    let wooconfig = get_wooconfig_address(&program_id).0;
     let wooammpool = get_wooammpool_address(
-                              &wooconfig,
-                              &token_mint_a,
-                              &token_mint_b,
-                              &program_id
-                          ).0;
+        &deployment.wooconfig,
+        &token_mint_a,
+        &token_mint_b,
+        &program_id,
+    )
+    .0;
 
     let account = client.get_account(&wooammpool).await?;
     let market_account = KeyedAccount {
@@ -50,7 +58,7 @@ async fn test_jupiter_quote() -> Result<(), Error> {
     let mut result = woofi_swap.quote(&QuoteParams {
         amount: 10000000,
         input_mint: SOL,
-        output_mint: USDC,
+        output_mint: usdc,
         swap_mode: SwapMode::ExactIn,
     })?;
 
@@ -62,7 +70,7 @@ async fn test_jupiter_quote() -> Result<(), Error> {
 
     result = woofi_swap.quote(&QuoteParams {
         amount: 200000000,
-        input_mint: USDC,
+        input_mint: usdc,
         output_mint: SOL,
         swap_mode: SwapMode::ExactIn,
     })?;
@@ -73,6 +81,20 @@ async fn test_jupiter_quote() -> Result<(), Error> {
     println!("result.fee_amount:{}", result.fee_amount);
     println!("result.fee_mint:{}", result.fee_mint);
 
+    result = woofi_swap.quote(&QuoteParams {
+        amount: 1000000,
+        input_mint: SOL,
+        output_mint: usdc,
+        swap_mode: SwapMode::ExactOut,
+    })?;
+
+    println!("Getting quote for selling SOL to receive 1 USDC");
+    println!("result.out_amount:{}", result.out_amount);
+    println!("result.in_amount:{}", result.in_amount);
+    println!("result.fee_amount:{}", result.fee_amount);
+    println!("result.fee_mint:{}", result.fee_mint);
+    assert!(result.out_amount >= 1000000);
+
     Ok(())
 }
 
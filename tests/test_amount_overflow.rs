@@ -0,0 +1,72 @@
+use jupiter_amm_interface::{Amm, QuoteParams, SwapMode};
+use woofi_jupiter::{
+    errors::QuoteError,
+    testing::{quote_params, MarketFixture, TokenSpec, FIXTURE_WOO},
+    util::{checked_to_u64, USDC},
+};
+
+// An 18 decimal token at 1 USDC: 100 USDC needs about 1e20 base units, past u64::MAX
+fn eighteen_decimal_market() -> MarketFixture {
+    let token = TokenSpec {
+        mint: FIXTURE_WOO,
+        decimals: 18,
+        price: 100_000_000,
+        ..TokenSpec::woo()
+    };
+    let mut fixture = MarketFixture::new(
+        woofi_jupiter::id(),
+        token,
+        TokenSpec::usdc(),
+        TokenSpec::usdc(),
+    );
+    fixture.token_a.woopool.cap_bal = u128::MAX;
+    fixture
+}
+
+fn buy_usdc_exact_out(amount: u64) -> QuoteParams {
+    quote_params(amount, FIXTURE_WOO, USDC, SwapMode::ExactOut)
+}
+
+#[test]
+fn test_checked_to_u64() {
+    assert_eq!(checked_to_u64(u64::MAX as u128).unwrap(), u64::MAX);
+    assert_eq!(
+        checked_to_u64(u64::MAX as u128 + 1).unwrap_err(),
+        QuoteError::AmountOverflow {
+            amount: u64::MAX as u128 + 1
+        }
+    );
+}
+
+#[test]
+fn test_huge_exact_out_input_errors() {
+    let woofi_swap = eighteen_decimal_market().woofi_swap().unwrap();
+
+    // 10 USDC still fits
+    let quote = woofi_swap.quote(&buy_usdc_exact_out(10_000_000)).unwrap();
+    assert!(quote.out_amount >= 10_000_000);
+    assert!(quote.in_amount > 10_000_000_000_000_000_000);
+
+    let error = woofi_swap
+        .quote(&buy_usdc_exact_out(100_000_000))
+        .unwrap_err();
+    match error.downcast_ref::<QuoteError>() {
+        // a truncated cast would have quoted amount % 2^64 instead
+        Some(QuoteError::AmountOverflow { amount }) => assert!(*amount > u64::MAX as u128),
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn test_apply_swap_overflow_leaves_state() {
+    let fixture = eighteen_decimal_market();
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+    let before = woofi_swap.quote(&buy_usdc_exact_out(10_000_000)).unwrap();
+
+    assert!(woofi_swap
+        .apply_swap(&buy_usdc_exact_out(100_000_000))
+        .is_err());
+
+    let after = woofi_swap.quote(&buy_usdc_exact_out(10_000_000)).unwrap();
+    assert_eq!(before.in_amount, after.in_amount);
+}
@@ -0,0 +1,84 @@
+use jupiter_amm_interface::{Amm, SwapMode};
+use solana_sdk::pubkey::Pubkey;
+use woofi_jupiter::{
+    cluster::{Cluster, WoofiDeployment, DEVNET_USDC},
+    testing::{quote_params, sell_sol, MarketFixture},
+    util::{get_wooconfig_address, SOL, USDC},
+    WoofiSwap,
+};
+
+#[test]
+fn test_deployments() {
+    let mainnet = WoofiDeployment::mainnet();
+    assert_eq!(mainnet.cluster, Cluster::MainnetBeta);
+    assert_eq!(mainnet.program_id, woofi_jupiter::id());
+    assert_eq!(mainnet.usdc_mint, USDC);
+    assert_eq!(
+        mainnet.wooconfig,
+        get_wooconfig_address(&mainnet.program_id).0
+    );
+
+    let devnet = WoofiDeployment::devnet();
+    assert_eq!(devnet.cluster, Cluster::Devnet);
+    assert_eq!(devnet.usdc_mint, DEVNET_USDC);
+    assert_ne!(devnet.program_id, mainnet.program_id);
+
+    assert_eq!(
+        WoofiDeployment::from_program_id(&devnet.program_id),
+        Some(devnet)
+    );
+    assert_eq!(
+        WoofiDeployment::from_program_id(&Pubkey::new_unique()),
+        None
+    );
+}
+
+#[test]
+fn test_cluster_from_str() {
+    for cluster in [Cluster::MainnetBeta, Cluster::Devnet] {
+        assert_eq!(cluster.to_string().parse::<Cluster>().unwrap(), cluster);
+    }
+    assert_eq!("mainnet".parse::<Cluster>().unwrap(), Cluster::MainnetBeta);
+    assert!("testnet".parse::<Cluster>().is_err());
+}
+
+#[test]
+fn test_quote_mainnet_and_devnet_side_by_side() {
+    let mainnet = MarketFixture::sol_usdc_on(&WoofiDeployment::mainnet());
+    let devnet = MarketFixture::sol_usdc_on(&WoofiDeployment::devnet());
+    assert_ne!(mainnet.pool_address, devnet.pool_address);
+
+    let mainnet_swap = mainnet.woofi_swap().unwrap();
+    let devnet_swap = devnet.woofi_swap().unwrap();
+
+    assert_eq!(mainnet_swap.cluster, Some(Cluster::MainnetBeta));
+    assert_eq!(mainnet_swap.program_id(), woofi_jupiter::id());
+    assert_eq!(devnet_swap.cluster, Some(Cluster::Devnet));
+    assert_eq!(
+        devnet_swap.program_id(),
+        WoofiDeployment::devnet().program_id
+    );
+
+    // same market state on both clusters quotes the same amounts in each cluster's USDC
+    let mainnet_quote = mainnet_swap.quote(&sell_sol()).unwrap();
+    let devnet_quote = devnet_swap
+        .quote(&quote_params(
+            1_000_000_000,
+            SOL,
+            DEVNET_USDC,
+            SwapMode::ExactIn,
+        ))
+        .unwrap();
+    assert_eq!(mainnet_quote.out_amount, devnet_quote.out_amount);
+    assert_eq!(mainnet_quote.fee_mint, USDC);
+    assert_eq!(devnet_quote.fee_mint, DEVNET_USDC);
+}
+
+#[test]
+fn test_from_keyed_account_unknown_owner() {
+    let fixture = MarketFixture::sol_usdc();
+    let mut keyed_account = fixture.keyed_account();
+    keyed_account.account.owner = Pubkey::new_unique();
+
+    assert!(WoofiSwap::from_keyed_account(&keyed_account, &fixture.amm_context()).is_err());
+}
@@ -0,0 +1,88 @@
+use woofi_jupiter::{
+    depth::{geometric_sizes, linear_sizes, DepthLadder},
+    limits::SwapLimit,
+    testing::MarketFixture,
+    util::{SOL, USDC},
+};
+
+#[test]
+fn test_ladder_sizes() {
+    assert_eq!(geometric_sizes(1, 10, 4), vec![1, 10, 100, 1_000]);
+    assert_eq!(geometric_sizes(u64::MAX / 2, 4, 3), vec![u64::MAX / 2]);
+    assert_eq!(linear_sizes(5, 3), vec![5, 10, 15]);
+    assert_eq!(
+        linear_sizes(u64::MAX / 2, 3),
+        vec![u64::MAX / 2, u64::MAX - 1]
+    );
+}
+
+#[test]
+fn test_depth_ladder_stops_at_limit() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    // 0.1 SOL up to 1M SOL
+    let ladder = woofi_swap
+        .depth_ladder(&SOL, &USDC, &geometric_sizes(100_000_000, 10, 8))
+        .unwrap();
+
+    // 10k SOL is past the 1M USDC max notional
+    assert_eq!(ladder.points.len(), 5);
+    assert_eq!(ladder.points.last().unwrap().in_amount, 1_000_000_000_000);
+    assert_eq!(ladder.limit, Some(SwapLimit::MaxNotional));
+
+    let marginal_price = ladder.marginal_price.unwrap();
+    for pair in ladder.points.windows(2) {
+        assert!(pair[1].execution_price <= pair[0].execution_price);
+        assert!(pair[1].price_impact_bps >= pair[0].price_impact_bps);
+    }
+    assert!(ladder
+        .points
+        .iter()
+        .all(|point| point.execution_price <= marginal_price));
+
+    assert_eq!(ladder.points[1].out_amount, 149_947_480);
+}
+
+#[test]
+fn test_depth_ladders_both_directions() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    let [sell_sol, buy_sol] = woofi_swap
+        .depth_ladders(
+            &linear_sizes(1_000_000_000, 5),
+            &linear_sizes(150_000_000, 5),
+        )
+        .unwrap();
+
+    assert_eq!(sell_sol.input_mint, SOL.to_string());
+    assert_eq!(buy_sol.input_mint, USDC.to_string());
+    assert_eq!(sell_sol.points.len(), 5);
+    assert_eq!(buy_sol.points.len(), 5);
+    assert_eq!(sell_sol.limit, None);
+    assert_eq!(buy_sol.limit, None);
+}
+
+#[test]
+fn test_depth_ladder_skips_below_min() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.min_swap_amount = 1_000_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    let ladder = woofi_swap
+        .depth_ladder(&SOL, &USDC, &geometric_sizes(100_000_000, 10, 4))
+        .unwrap();
+    let sizes: Vec<u64> = ladder.points.iter().map(|point| point.in_amount).collect();
+    assert_eq!(sizes, vec![10_000_000_000, 100_000_000_000]);
+}
+
+#[test]
+fn test_depth_ladder_json() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+    let ladder = woofi_swap
+        .depth_ladder(&SOL, &USDC, &geometric_sizes(100_000_000, 10, 8))
+        .unwrap();
+
+    let json = ladder.to_json().unwrap();
+    assert!(json.contains("\"limit\":\"MaxNotional\""));
+    assert_eq!(serde_json::from_str::<DepthLadder>(&json).unwrap(), ladder);
+}
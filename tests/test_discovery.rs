@@ -0,0 +1,163 @@
+use jupiter_amm_interface::{Amm, SwapMode};
+use serde_json::{json, Value};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use woofi_jupiter::{
+    discovery::{discover_pools, MarketCacheEntry},
+    errors::DiscoveryError,
+    testing::{anchor_account, quote_params, sell_sol, MarketFixture, TokenSpec},
+    util::SOL,
+    WoofiSwap,
+};
+
+// What getProgramAccounts returns for the fixtures: every account the program owns
+fn program_accounts(fixtures: &[&MarketFixture]) -> Vec<(Pubkey, Account)> {
+    let mut accounts = Vec::new();
+    for fixture in fixtures {
+        accounts.push((fixture.pool_address, fixture.keyed_account().account));
+        accounts.extend(
+            fixture
+                .account_map()
+                .into_iter()
+                .filter(|(_, account)| account.owner == fixture.program_id),
+        );
+    }
+    accounts
+}
+
+#[test]
+fn test_discover_pools() {
+    let (sol_usdc, sol_woo) = (MarketFixture::sol_usdc(), MarketFixture::sol_woo());
+    let discovery = discover_pools(
+        &woofi_jupiter::id(),
+        &program_accounts(&[&sol_usdc, &sol_woo]),
+    );
+
+    let mut expected = vec![sol_usdc.pool_address, sol_woo.pool_address];
+    expected.sort();
+    let addresses: Vec<Pubkey> = discovery.pools.iter().map(|pool| pool.address).collect();
+    assert_eq!(addresses, expected);
+    assert!(discovery.rejected.is_empty());
+
+    // the discovered markets load and quote like the hand-maintained ones
+    let keyed_accounts = discovery.keyed_accounts();
+    for fixture in [&sol_usdc, &sol_woo] {
+        let keyed_account = keyed_accounts
+            .iter()
+            .find(|keyed_account| keyed_account.key == fixture.pool_address)
+            .unwrap();
+        assert_eq!(keyed_account.params, None);
+        let mut woofi_swap =
+            WoofiSwap::from_keyed_account(keyed_account, &fixture.amm_context()).unwrap();
+        woofi_swap.update(&fixture.account_map()).unwrap();
+
+        let quote = woofi_swap
+            .quote(&quote_params(
+                1_000_000_000,
+                SOL,
+                fixture.token_b.mint,
+                SwapMode::ExactIn,
+            ))
+            .unwrap();
+        assert!(quote.out_amount > 0);
+    }
+}
+
+#[test]
+fn test_discover_pools_rejections() {
+    let fixture = MarketFixture::sol_usdc();
+    let program_id = fixture.program_id;
+    let pool_account = fixture.keyed_account().account;
+
+    let moved = Pubkey::new_unique();
+    let foreign = Pubkey::new_unique();
+    let mut foreign_account = pool_account.clone();
+    foreign_account.owner = Pubkey::new_unique();
+
+    let truncated = Pubkey::new_unique();
+    let mut truncated_account = pool_account.clone();
+    truncated_account.data.truncate(40);
+
+    let other_config = Pubkey::new_unique();
+    let mut woo_amm_pool = fixture.woo_amm_pool();
+    woo_amm_pool.wooconfig = Pubkey::new_unique();
+
+    let accounts = vec![
+        (moved, pool_account),
+        (foreign, foreign_account.clone()),
+        (truncated, truncated_account),
+        (other_config, anchor_account(&woo_amm_pool, &program_id)),
+    ];
+    let discovery = discover_pools(&program_id, &accounts);
+
+    assert!(discovery.pools.is_empty());
+    assert_eq!(
+        discovery.rejected,
+        vec![
+            (
+                moved,
+                DiscoveryError::AddressMismatch {
+                    expected: fixture.pool_address
+                }
+            ),
+            (
+                foreign,
+                DiscoveryError::WrongOwner {
+                    owner: foreign_account.owner
+                }
+            ),
+            (truncated, DiscoveryError::Undecodable),
+            (
+                other_config,
+                DiscoveryError::WooconfigMismatch {
+                    wooconfig: woo_amm_pool.wooconfig,
+                    expected: fixture.wooconfig_address,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_market_cache_json() {
+    let fixture = MarketFixture::sol_usdc();
+    let discovery = discover_pools(&fixture.program_id, &program_accounts(&[&fixture]));
+
+    let json: Value = serde_json::from_str(&discovery.market_cache_json().unwrap()).unwrap();
+    let entry = &json[0];
+    assert_eq!(entry["pubkey"], json!(fixture.pool_address.to_string()));
+    assert_eq!(entry["owner"], json!(fixture.program_id.to_string()));
+    assert_eq!(entry["data"][1], json!("base64"));
+    assert!(entry.get("rentEpoch").is_some());
+    assert!(entry.get("params").is_none());
+
+    let entries: Vec<MarketCacheEntry> = serde_json::from_value(json).unwrap();
+    let keyed_account = entries[0].keyed_account().unwrap();
+    assert_eq!(keyed_account.key, fixture.pool_address);
+    assert_eq!(keyed_account.account, fixture.keyed_account().account);
+    assert_eq!(entries[0].space, keyed_account.account.data.len());
+}
+
+#[test]
+fn test_custom_deployment_entries_carry_program_id() {
+    let program_id = Pubkey::new_unique();
+    let fixture = MarketFixture::new(
+        program_id,
+        TokenSpec::sol(),
+        TokenSpec::usdc(),
+        TokenSpec::usdc(),
+    );
+    let discovery = discover_pools(&program_id, &program_accounts(&[&fixture]));
+
+    let entry = &discovery.market_cache()[0];
+    assert_eq!(
+        entry.params,
+        Some(json!({ "programId": program_id.to_string() }))
+    );
+
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&entry.keyed_account().unwrap(), &fixture.amm_context())
+            .unwrap();
+    woofi_swap.update(&fixture.account_map()).unwrap();
+    assert_eq!(woofi_swap.program_id(), program_id);
+    assert!(woofi_swap.quote(&sell_sol()).is_ok());
+}
@@ -0,0 +1,55 @@
+use jupiter_amm_interface::{Amm, SwapMode};
+use rust_decimal::Decimal;
+use woofi_jupiter::{
+    testing::{quote_params, MarketFixture, FIXTURE_WOO},
+    util::{fee_rate_to_pct, SOL, USDC},
+};
+
+#[test]
+fn test_fee_rate_to_pct() {
+    assert_eq!(fee_rate_to_pct(0), Decimal::ZERO);
+    // 2.5 bps
+    assert_eq!(fee_rate_to_pct(25), Decimal::new(25, 5));
+    assert_eq!(fee_rate_to_pct(1_000), Decimal::new(1, 2));
+    assert_eq!(fee_rate_to_pct(u16::MAX), Decimal::new(65_535, 5));
+}
+
+#[test]
+fn test_quote_fee_pct() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    for (input_mint, output_mint, amount) in [(SOL, USDC, 1_000_000_000), (USDC, SOL, 150_000_000)]
+    {
+        let result = woofi_swap
+            .quote(&quote_params(
+                amount,
+                input_mint,
+                output_mint,
+                SwapMode::ExactIn,
+            ))
+            .unwrap();
+        assert_eq!(result.fee_pct, Decimal::new(25, 5));
+    }
+}
+
+#[test]
+fn test_quote_fee_pct_base_to_base() {
+    let mut fixture = MarketFixture::sol_woo();
+    fixture.token_b.woopool.fee_rate = 40;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    let result = woofi_swap
+        .quote(&quote_params(
+            1_000_000_000,
+            SOL,
+            FIXTURE_WOO,
+            SwapMode::ExactIn,
+        ))
+        .unwrap();
+
+    // the larger of the two pool fees applies
+    assert_eq!(result.fee_pct, Decimal::new(4, 4));
+    // selling 1 SOL pays out 149_984_977 USDC on the quote leg (37_497 in fees at the SOL pool's
+    // 2.5 bps), so the fee is ceil(149_984_977 * 40 / 1e5) = 59_994
+    assert_eq!(result.fee_amount, 59_994);
+}
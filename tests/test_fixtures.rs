@@ -0,0 +1,115 @@
+use anchor_lang::AccountDeserialize;
+use jupiter_amm_interface::{Amm, SwapMode};
+use woofi_jupiter::{
+    state::{WooAmmPool, WooPool, Wooracle},
+    testing::{quote_params, sell_sol, MarketFixture, FIXTURE_WOO},
+    util::{SOL, USDC},
+    WoofiSwap,
+};
+
+#[test]
+fn test_fixture_accounts_deserialize() {
+    let fixture = MarketFixture::sol_usdc();
+    let account_map = fixture.account_map();
+
+    let pool =
+        WooAmmPool::try_deserialize(&mut fixture.keyed_account().account.data.as_slice()).unwrap();
+    assert_eq!(pool.token_mint_a, SOL);
+    assert_eq!(pool.token_mint_b, USDC);
+    assert_eq!(pool.quote_woopool, pool.woopool_b);
+
+    let woopool =
+        WooPool::try_deserialize(&mut account_map[&pool.woopool_a].data.as_slice()).unwrap();
+    assert_eq!(woopool.token_mint, SOL);
+    assert_eq!(woopool.quote_token_mint, USDC);
+
+    let wooracle =
+        Wooracle::try_deserialize(&mut account_map[&pool.wooracle_a].data.as_slice()).unwrap();
+    assert_eq!(wooracle.price, 15_000_000_000);
+    assert_eq!(wooracle.feed_account, pool.feed_account_a);
+}
+
+#[test]
+fn test_quote_sol_usdc_offline() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    let result = woofi_swap.quote(&sell_sol()).unwrap();
+    assert_eq!(result.in_amount, 1_000_000_000);
+    assert_eq!(result.out_amount, 149_947_480);
+    assert_eq!(result.fee_amount, 37_497);
+    assert_eq!(result.fee_mint, USDC);
+
+    let result = woofi_swap
+        .quote(&quote_params(150_000_000, USDC, SOL, SwapMode::ExactIn))
+        .unwrap();
+    assert_eq!(result.in_amount, 150_000_000);
+    assert_eq!(result.out_amount, 999_649_875);
+    assert_eq!(result.fee_amount, 37_500);
+}
+
+#[test]
+fn test_quote_exact_out_offline() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    for (input_mint, output_mint, amount) in [(SOL, USDC, 150_000_000), (USDC, SOL, 1_000_000_000)]
+    {
+        let result = woofi_swap
+            .quote(&quote_params(
+                amount,
+                input_mint,
+                output_mint,
+                SwapMode::ExactOut,
+            ))
+            .unwrap();
+        assert!(result.out_amount >= amount);
+
+        // one unit less of input no longer covers the requested output
+        let below = woofi_swap
+            .quote(&quote_params(
+                result.in_amount - 1,
+                input_mint,
+                output_mint,
+                SwapMode::ExactIn,
+            ))
+            .unwrap();
+        assert!(below.out_amount < amount);
+    }
+}
+
+#[test]
+fn test_quote_base_to_base_offline() {
+    let woofi_swap = MarketFixture::sol_woo().woofi_swap().unwrap();
+
+    let result = woofi_swap
+        .quote(&quote_params(
+            1_000_000_000,
+            SOL,
+            FIXTURE_WOO,
+            SwapMode::ExactIn,
+        ))
+        .unwrap();
+    assert_eq!(result.out_amount, 599_609_533);
+    assert_eq!(result.fee_amount, 37_497);
+    assert_eq!(result.fee_mint, USDC);
+}
+
+#[test]
+fn test_quote_paused_offline() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.wooconfig.paused = true;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    assert!(woofi_swap.quote(&sell_sol()).is_err());
+}
+
+#[test]
+fn test_update_missing_account() {
+    let fixture = MarketFixture::sol_usdc();
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+
+    let mut account_map = fixture.account_map();
+    account_map.remove(&fixture.token_a.wooracle_address);
+
+    assert!(woofi_swap.update(&account_map).is_err());
+}
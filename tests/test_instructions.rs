@@ -0,0 +1,164 @@
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+use woofi_jupiter::{
+    instructions::{
+        swap, try_query, SwapPoolAccounts, SwapUserAccounts, TokenAccounts, SWAP_DISCRIMINATOR,
+        TRY_QUERY_DISCRIMINATOR,
+    },
+    state::WooAmmPool,
+    util::{get_wooconfig_address, get_woopool_address, get_wooracle_address},
+};
+
+fn token_accounts() -> TokenAccounts {
+    TokenAccounts {
+        token_mint: Pubkey::new_unique(),
+        feed_account: Pubkey::new_unique(),
+        price_update: Pubkey::new_unique(),
+        token_vault: Pubkey::new_unique(),
+    }
+}
+
+fn woo_amm_pool(
+    program_id: &Pubkey,
+    token_a: &TokenAccounts,
+    token_b: &TokenAccounts,
+    quote: &TokenAccounts,
+) -> WooAmmPool {
+    let wooconfig = get_wooconfig_address(program_id).0;
+    let wooracle = |token: &TokenAccounts| {
+        get_wooracle_address(
+            &wooconfig,
+            &token.token_mint,
+            &token.feed_account,
+            &token.price_update,
+            program_id,
+        )
+        .0
+    };
+    let woopool = |token: &TokenAccounts| {
+        get_woopool_address(&wooconfig, &token.token_mint, &quote.token_mint, program_id).0
+    };
+
+    WooAmmPool {
+        wooconfig,
+        wooammpool_bump: [255],
+        authority: Pubkey::new_unique(),
+        wooracle_a: wooracle(token_a),
+        woopool_a: woopool(token_a),
+        feed_account_a: token_a.feed_account,
+        price_update_a: token_a.price_update,
+        token_mint_a: token_a.token_mint,
+        token_vault_a: token_a.token_vault,
+        wooracle_b: wooracle(token_b),
+        woopool_b: woopool(token_b),
+        feed_account_b: token_b.feed_account,
+        price_update_b: token_b.price_update,
+        token_mint_b: token_b.token_mint,
+        token_vault_b: token_b.token_vault,
+        quote_token_mint: quote.token_mint,
+        quote_feed_account: quote.feed_account,
+        quote_price_update: quote.price_update,
+        quote_woopool: woopool(quote),
+        quote_vault: quote.token_vault,
+    }
+}
+
+fn user_accounts() -> SwapUserAccounts {
+    SwapUserAccounts {
+        payer: Pubkey::new_unique(),
+        token_owner_account_from: Pubkey::new_unique(),
+        token_owner_account_to: Pubkey::new_unique(),
+        rebate_to: Pubkey::new_unique(),
+    }
+}
+
+#[test]
+fn test_swap_instruction() {
+    let program_id = woofi_jupiter::id();
+    let (token_a, token_b, quote) = (token_accounts(), token_accounts(), token_accounts());
+    let pool = woo_amm_pool(&program_id, &token_a, &token_b, &quote);
+    let user = user_accounts();
+
+    let pool_accounts =
+        SwapPoolAccounts::from_woo_amm_pool(&pool, &token_b.token_mint, &token_a.token_mint)
+            .unwrap();
+    let ix = swap(&program_id, &pool_accounts, &user, 1_000_000, 999_000).unwrap();
+
+    assert_eq!(ix.program_id, program_id);
+
+    let mut data = SWAP_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&1_000_000_u128.to_le_bytes());
+    data.extend_from_slice(&999_000_u128.to_le_bytes());
+    assert_eq!(ix.data, data);
+
+    assert_eq!(
+        ix.accounts,
+        vec![
+            AccountMeta::new_readonly(pool.wooconfig, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(user.payer, true),
+            AccountMeta::new(pool.wooracle_b, false),
+            AccountMeta::new(pool.woopool_b, false),
+            AccountMeta::new(user.token_owner_account_from, false),
+            AccountMeta::new(pool.token_vault_b, false),
+            AccountMeta::new_readonly(pool.price_update_b, false),
+            AccountMeta::new(pool.wooracle_a, false),
+            AccountMeta::new(pool.woopool_a, false),
+            AccountMeta::new(user.token_owner_account_to, false),
+            AccountMeta::new(pool.token_vault_a, false),
+            AccountMeta::new_readonly(pool.price_update_a, false),
+            AccountMeta::new(pool.quote_woopool, false),
+            AccountMeta::new_readonly(pool.quote_price_update, false),
+            AccountMeta::new(pool.quote_vault, false),
+            AccountMeta::new_readonly(user.rebate_to, false),
+        ]
+    );
+}
+
+#[test]
+fn test_try_query_instruction() {
+    let program_id = woofi_jupiter::id();
+    let (token_a, token_b, quote) = (token_accounts(), token_accounts(), token_accounts());
+    let pool = woo_amm_pool(&program_id, &token_a, &token_b, &quote);
+
+    let pool_accounts =
+        SwapPoolAccounts::from_woo_amm_pool(&pool, &token_a.token_mint, &token_b.token_mint)
+            .unwrap();
+    let ix = try_query(&program_id, &pool_accounts, 42).unwrap();
+
+    let mut data = TRY_QUERY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&42_u128.to_le_bytes());
+    assert_eq!(ix.data, data);
+
+    assert_eq!(ix.accounts.len(), 12);
+    assert!(ix
+        .accounts
+        .iter()
+        .all(|meta| !meta.is_writable && !meta.is_signer));
+    assert_eq!(ix.accounts[1].pubkey, pool.wooracle_a);
+    assert_eq!(ix.accounts[5].pubkey, pool.wooracle_b);
+    assert_eq!(ix.accounts[11].pubkey, pool.quote_vault);
+}
+
+#[test]
+fn test_pool_accounts_from_token_accounts() {
+    let program_id = woofi_jupiter::id();
+    let (token_a, token_b, quote) = (token_accounts(), token_accounts(), token_accounts());
+    let pool = woo_amm_pool(&program_id, &token_a, &token_b, &quote);
+
+    assert_eq!(
+        SwapPoolAccounts::from_token_accounts(&program_id, &token_a, &token_b, &quote),
+        SwapPoolAccounts::from_woo_amm_pool(&pool, &token_a.token_mint, &token_b.token_mint)
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_pool_accounts_unknown_mint() {
+    let program_id = woofi_jupiter::id();
+    let (token_a, token_b, quote) = (token_accounts(), token_accounts(), token_accounts());
+    let pool = woo_amm_pool(&program_id, &token_a, &token_b, &quote);
+
+    assert!(
+        SwapPoolAccounts::from_woo_amm_pool(&pool, &token_a.token_mint, &quote.token_mint).is_err()
+    );
+}
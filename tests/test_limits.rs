@@ -0,0 +1,139 @@
+use jupiter_amm_interface::{Amm, SwapMode};
+use solana_sdk::pubkey::Pubkey;
+use woofi_jupiter::{
+    errors::QuoteError,
+    limits::{MaxSwapSize, SwapLimit},
+    testing::{quote_params, MarketFixture, FIXTURE_WOO},
+    util::{SOL, USDC},
+    WoofiSwap,
+};
+
+fn quote_in(
+    woofi_swap: &WoofiSwap,
+    amount: u128,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+) -> anyhow::Result<u64> {
+    woofi_swap
+        .quote(&quote_params(
+            amount as u64,
+            input_mint,
+            output_mint,
+            SwapMode::ExactIn,
+        ))
+        .map(|quote| quote.out_amount)
+}
+
+// the max size quotes, one more unit runs into the reported limit
+fn assert_tight(woofi_swap: &WoofiSwap, input_mint: Pubkey, output_mint: Pubkey) -> MaxSwapSize {
+    let max_size = woofi_swap.max_swap_size(&input_mint, &output_mint).unwrap();
+
+    let out_amount = quote_in(woofi_swap, max_size.in_amount, input_mint, output_mint).unwrap();
+    assert_eq!(out_amount as u128, max_size.out_amount);
+
+    let error = quote_in(woofi_swap, max_size.in_amount + 1, input_mint, output_mint).unwrap_err();
+    assert_eq!(SwapLimit::from_error(&error), Some(max_size.limit));
+
+    max_size
+}
+
+#[test]
+fn test_max_swap_size_notional() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    let max_size = assert_tight(&woofi_swap, SOL, USDC);
+    // 1M USDC of notional at $150
+    assert_eq!(max_size.in_amount, 6_666_666_666_673);
+    assert_eq!(max_size.limit, SwapLimit::MaxNotional);
+}
+
+#[test]
+fn test_max_swap_size_every_direction() {
+    for fixture in [MarketFixture::sol_usdc(), MarketFixture::sol_woo()] {
+        let woofi_swap = fixture.woofi_swap().unwrap();
+        let (a, b) = (fixture.token_a.mint, fixture.token_b.mint);
+
+        assert_tight(&woofi_swap, a, b);
+        assert_tight(&woofi_swap, b, a);
+    }
+}
+
+#[test]
+fn test_max_swap_size_gamma() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.max_gamma = 1_000_000_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    let max_size = assert_tight(&woofi_swap, SOL, USDC);
+    assert_eq!(max_size.in_amount, 6_666_666_666);
+    assert_eq!(max_size.limit, SwapLimit::MaxGamma);
+}
+
+#[test]
+fn test_max_swap_size_balance_cap() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.cap_bal = fixture.token_a.vault_amount as u128 + 5_000_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    let max_size = assert_tight(&woofi_swap, SOL, USDC);
+    assert_eq!(max_size.in_amount, 5_000_000_000);
+    assert_eq!(max_size.limit, SwapLimit::BalanceCap);
+}
+
+#[test]
+fn test_max_swap_size_spread_plus_gamma() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.wooracle.coeff = 1_000_000_000_000_000;
+    fixture.token_a.woopool.max_gamma = u128::MAX;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    let max_size = assert_tight(&woofi_swap, SOL, USDC);
+    assert_eq!(max_size.limit, SwapLimit::SpreadPlusGamma);
+}
+
+#[test]
+fn test_max_swap_size_reserves() {
+    let mut fixture = MarketFixture::sol_usdc();
+    // USDC is token b of this market
+    fixture.token_b.vault_amount = 100_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+    assert_eq!(
+        assert_tight(&woofi_swap, SOL, USDC).limit,
+        SwapLimit::QuoteReserves
+    );
+
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.vault_amount = 10_000_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+    let max_size = assert_tight(&woofi_swap, USDC, SOL);
+    assert_eq!(max_size.limit, SwapLimit::DestinationReserves);
+    assert!(max_size.out_amount <= 10_000_000_000);
+}
+
+#[test]
+fn test_max_swap_size_errors() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.wooconfig.paused = true;
+    let error = fixture
+        .woofi_swap()
+        .unwrap()
+        .max_swap_size(&SOL, &USDC)
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<QuoteError>(),
+        Some(&QuoteError::Paused)
+    );
+
+    // every size the quote pool allows is below its own minimum
+    let mut fixture = MarketFixture::sol_woo();
+    fixture.quote.woopool.min_swap_amount = 10_000_000_000_000;
+    let error = fixture
+        .woofi_swap()
+        .unwrap()
+        .max_swap_size(&SOL, &FIXTURE_WOO)
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<QuoteError>(),
+        Some(QuoteError::BelowMinSwapAmount { .. })
+    ));
+}
@@ -0,0 +1,195 @@
+use num_bigint::BigUint;
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+use woofi_jupiter::{
+    errors::QuoteError,
+    state::WooPool,
+    testing::WooPoolBuilder,
+    util::{
+        calc_base_amount_sell_quote, calc_quote_amount_sell_base, checked_mul_div,
+        checked_mul_div_round_up, mul_div, Decimals, GetStateResult, Rounding,
+    },
+};
+
+// n0 * n1 / d on big integers, None when the quotient does not fit in u128
+fn reference_mul_div(n0: u128, n1: u128, d: u128, rounding: Rounding) -> Option<u128> {
+    let product = BigUint::from(n0) * BigUint::from(n1);
+    let d = BigUint::from(d);
+    let mut quotient = &product / &d;
+    if rounding == Rounding::Up && (&product % &d) != BigUint::from(0_u32) {
+        quotient += 1_u32;
+    }
+    u128::try_from(quotient).ok()
+}
+
+fn rounding() -> impl Strategy<Value = Rounding> {
+    prop_oneof![Just(Rounding::Down), Just(Rounding::Up)]
+}
+
+// full range values plus ones near the edges of u64 and u128
+fn operand() -> impl Strategy<Value = u128> {
+    prop_oneof![
+        any::<u128>(),
+        any::<u64>().prop_map(u128::from),
+        (0..1_000_u128).prop_map(|v| u128::MAX - v),
+        (0..1_000_u128),
+    ]
+}
+
+#[test]
+fn test_mul_div_wide_product() {
+    // the product overflows u128 but the quotient does not
+    assert_eq!(checked_mul_div(u128::MAX, 2, 4), Ok(u128::MAX / 2));
+    assert_eq!(
+        checked_mul_div(u128::MAX, u128::MAX, u128::MAX),
+        Ok(u128::MAX)
+    );
+    assert_eq!(checked_mul_div(1 << 100, 1 << 100, 1 << 90), Ok(1 << 110));
+    assert_eq!(
+        checked_mul_div_round_up(u128::MAX, 3, 6),
+        Ok(u128::MAX / 2 + 1)
+    );
+
+    assert!(checked_mul_div(u128::MAX, 2, 1).is_err());
+    assert!(mul_div(1, 1, 0, Rounding::Down).is_err());
+    // rounding up past u128::MAX
+    assert!(mul_div(u128::MAX, u128::MAX, u128::MAX - 1, Rounding::Up).is_err());
+}
+
+#[test]
+fn test_mul_div_rounding() {
+    assert_eq!(mul_div(10, 10, 3, Rounding::Down), Ok(33));
+    assert_eq!(mul_div(10, 10, 3, Rounding::Up), Ok(34));
+    assert_eq!(mul_div(10, 9, 3, Rounding::Up), Ok(30));
+    assert_eq!(mul_div(0, 9, 3, Rounding::Up), Ok(0));
+}
+
+#[test]
+fn test_sell_base_high_decimals() {
+    // 1e12 tokens of an 18 decimal token at $10k: base_amount * price overflowed u128
+    let woopool = WooPoolBuilder::new(
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+    )
+    .base_decimals(18)
+    .build();
+    let decimals = Decimals::new(8, 6, 18).unwrap();
+    let state = GetStateResult {
+        price_out: 1_000_000_000_000,
+        spread: 0,
+        coeff: 0,
+        feasible_out: true,
+    };
+
+    let error = calc_quote_amount_sell_base(
+        1_000_000_000_000_000_000_000_000_000_000,
+        &woopool,
+        &decimals,
+        &state,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<QuoteError>(),
+        Some(QuoteError::MaxNotionalExceeded { .. })
+    ));
+}
+
+fn high_decimals_pool() -> WooPool {
+    WooPoolBuilder::new(
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+    )
+    .base_decimals(18)
+    .max_notional_swap(u128::MAX)
+    .max_gamma(u128::MAX)
+    .build()
+}
+
+// calc_base_amount_sell_quote on big integers, following the rounding contract in swap_math
+fn reference_sell_quote(
+    quote_amount: u128,
+    decimals: &Decimals,
+    state: &GetStateResult,
+) -> (u128, u128) {
+    let one_e18 = BigUint::from(1_000_000_000_000_000_000_u128);
+    let quote = BigUint::from(quote_amount);
+    let price = BigUint::from(state.price_out);
+
+    let gamma = &quote * state.coeff / decimals.quote_dec;
+    let b = &quote * decimals.base_dec * decimals.price_dec / &price;
+    let d = b * (&one_e18 - &gamma - state.spread) / &one_e18;
+    let base = d / decimals.quote_dec;
+    let new_price = &one_e18 * &price / (&one_e18 - &gamma);
+
+    (
+        u128::try_from(base).unwrap(),
+        u128::try_from(new_price).unwrap(),
+    )
+}
+
+#[test]
+fn test_sell_quote_high_decimals() {
+    // 1e15 USDC into an 18 decimal token: quote_amount * base_dec alone is past u128::MAX
+    let woopool = high_decimals_pool();
+    let decimals = Decimals::new(8, 6, 18).unwrap();
+    let state = GetStateResult {
+        price_out: 1_234_567_891,
+        spread: 1_000_000_000_000_000,
+        coeff: 1,
+        feasible_out: true,
+    };
+
+    let quote_amount: u128 = 1_000_000_000_000_000_000_000;
+    assert!(quote_amount
+        .checked_mul(decimals.base_dec as u128)
+        .is_none());
+
+    assert_eq!(
+        calc_base_amount_sell_quote(quote_amount, &woopool, &decimals, &state).unwrap(),
+        reference_sell_quote(quote_amount, &decimals, &state)
+    );
+}
+
+proptest! {
+    #[test]
+    fn prop_mul_div_matches_big_integers(n0 in operand(), n1 in operand(), d in operand(), rounding in rounding()) {
+        prop_assume!(d > 0);
+        prop_assert_eq!(mul_div(n0, n1, d, rounding).ok(), reference_mul_div(n0, n1, d, rounding));
+    }
+
+    // quotients that always fit, so every case exercises the 256-bit path
+    #[test]
+    fn prop_mul_div_fitting_quotient(n0 in any::<u128>(), n1 in any::<u128>(), rounding in rounding()) {
+        let d = n1.max(1);
+        let expected = reference_mul_div(n0, n1, d, rounding);
+        prop_assert!(expected.is_some());
+        prop_assert_eq!(mul_div(n0, n1, d, rounding).ok(), expected);
+    }
+
+    // quote amounts past u128::MAX / 1e18, which overflowed before mul_div took the base decimals
+    #[test]
+    fn prop_sell_quote_high_decimals_matches_big_integers(
+        quote_amount in 340_282_366_920_938_463_464_u128..1_000_000_000_000_000_000_000_000,
+        price_out in 10_000_000_000_000_u128..1_000_000_000_000_000,
+        spread in 0_u64..10_000_000_000_000_000,
+    ) {
+        let decimals = Decimals::new(8, 6, 18).unwrap();
+        let state = GetStateResult {
+            price_out,
+            spread,
+            coeff: 0,
+            feasible_out: true,
+        };
+
+        prop_assert_eq!(
+            calc_base_amount_sell_quote(quote_amount, &high_decimals_pool(), &decimals, &state).unwrap(),
+            reference_sell_quote(quote_amount, &decimals, &state)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use jupiter_amm_interface::{Amm, KeyedAccount};
+use serde_json::json;
+use woofi_jupiter::{
+    errors::{OracleFailure, QuoteError},
+    testing::{sell_sol, MarketFixture, PriceUpdateBuilder},
+    util::{OracleGuard, SOL},
+    WoofiSwap,
+};
+
+// bound of the fixture wooracles, 1%
+const FIXTURE_BOUND: u64 = 10_000_000_000_000_000;
+
+// SOL at $150 with a 2% confidence interval
+fn wide_sol_price_update(fixture: &MarketFixture) -> PriceUpdateBuilder {
+    PriceUpdateBuilder::new(fixture.token_a.feed_account)
+        .price(15_000_000_000, -8)
+        .conf(300_000_000)
+        .publish_time(fixture.clock.unix_timestamp)
+        .posted_slot(fixture.clock.slot)
+}
+
+fn strict_guard() -> OracleGuard {
+    OracleGuard {
+        max_conf_ratio: Some(10_000_000_000_000_000),
+        ..OracleGuard::default()
+    }
+}
+
+#[test]
+fn test_wide_conf_rejected_without_params() {
+    // the fixture wooracle's bound is 1%, which the 2% interval exceeds
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.price_update = wide_sol_price_update(&fixture).build();
+    let woofi_swap = fixture.woofi_swap().unwrap();
+    assert_eq!(woofi_swap.oracle_guard_a, OracleGuard::default());
+
+    assert!(!woofi_swap.state_a.unwrap().feasible_out);
+    let error = woofi_swap.quote(&sell_sol()).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<QuoteError>(),
+        Some(&QuoteError::OracleNotFeasible {
+            mint: SOL,
+            reason: Some(OracleFailure::ConfidenceTooWide),
+        })
+    );
+}
+
+#[test]
+fn test_conf_within_limit() {
+    // without max_conf_ratio the wooracle bound is the limit
+    let guard = OracleGuard::default();
+    assert_eq!(guard.max_conf_ratio(FIXTURE_BOUND), FIXTURE_BOUND as u128);
+    assert_eq!(
+        strict_guard().max_conf_ratio(50_000_000_000_000_000),
+        10_000_000_000_000_000
+    );
+
+    // exactly 1% passes, anything wider does not
+    assert!(guard.conf_within_limit(15_000_000_000, 150_000_000, FIXTURE_BOUND));
+    assert!(!guard.conf_within_limit(15_000_000_000, 150_000_001, FIXTURE_BOUND));
+    assert!(guard.conf_within_limit(15_000_000_000, 0, FIXTURE_BOUND));
+
+    assert!(!guard.conf_within_limit(0, 0, FIXTURE_BOUND));
+    assert!(!guard.conf_within_limit(-15_000_000_000, 0, FIXTURE_BOUND));
+}
+
+#[test]
+fn test_wide_conf_marks_oracle_infeasible() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.price_update = wide_sol_price_update(&fixture).build();
+    let keyed_account = KeyedAccount {
+        params: Some(json!({ "maxConfRatio": 10_000_000_000_000_000_u64 })),
+        ..fixture.keyed_account()
+    };
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&keyed_account, &fixture.amm_context()).unwrap();
+    assert_eq!(woofi_swap.oracle_guard_a, strict_guard());
+    woofi_swap.update(&fixture.account_map()).unwrap();
+
+    assert!(!woofi_swap.state_a.unwrap().feasible_out);
+    assert!(woofi_swap.state_b.unwrap().feasible_out);
+
+    let error = woofi_swap.quote(&sell_sol()).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<QuoteError>(),
+        Some(&QuoteError::OracleNotFeasible {
+            mint: SOL,
+            reason: Some(OracleFailure::ConfidenceTooWide),
+        })
+    );
+}
+
+#[test]
+fn test_wide_quote_conf_marks_both_sides_infeasible() {
+    // 2% on the USDC feed, past the 1% bound of both wooracles
+    let mut fixture = MarketFixture::sol_woo();
+    fixture.quote.price_update.price_message.conf = 2_000_000;
+
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    assert!(!woofi_swap.state_a.unwrap().feasible_out);
+    assert!(!woofi_swap.state_b.unwrap().feasible_out);
+}
+
+#[test]
+fn test_oracle_guard_is_configurable() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.price_update = wide_sol_price_update(&fixture).build();
+
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+    woofi_swap.oracle_guard_a = OracleGuard {
+        max_conf_ratio: Some(50_000_000_000_000_000),
+        ..OracleGuard::default()
+    };
+    woofi_swap.update(&fixture.account_map()).unwrap();
+
+    assert!(woofi_swap.state_a.unwrap().feasible_out);
+    assert!(woofi_swap.quote(&sell_sol()).is_ok());
+}
@@ -0,0 +1,129 @@
+use woofi_jupiter::{
+    testing::{MarketFixture, FIXTURE_UNIX_TIMESTAMP},
+    util::{OracleHealth, SOL, USDC},
+    WoofiSwap,
+};
+
+fn sol_health(woofi_swap: &WoofiSwap) -> OracleHealth {
+    let [(mint, health), _] = woofi_swap.oracle_health().unwrap();
+    assert_eq!(mint, SOL);
+    health
+}
+
+#[test]
+fn test_oracle_health_feasible() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    assert_eq!(
+        sol_health(&woofi_swap),
+        OracleHealth {
+            wo_price: 15_000_000_000,
+            clo_price: 15_000_000_000,
+            lower_bound: 14_850_000_000,
+            upper_bound: 15_150_000_000,
+            updated_at: FIXTURE_UNIX_TIMESTAMP,
+            wo_age: 0,
+            stale_duration: 120,
+            pyth_age: 0,
+            quote_pyth_age: 0,
+            maximum_age: 60,
+            pyth_usable: true,
+            conf_within_limit: true,
+            range_min: 1_500_000_000,
+            range_max: 150_000_000_000,
+            feasible: true,
+        }
+    );
+
+    let [_, (mint, health)] = woofi_swap.oracle_health().unwrap();
+    assert_eq!(mint, USDC);
+    assert!(health.feasible);
+}
+
+#[test]
+fn test_oracle_health_stale_wooracle() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.advance_clock(200);
+    fixture.refresh_price_updates();
+    let health = sol_health(&fixture.woofi_swap().unwrap());
+
+    assert_eq!(health.wo_age, 200);
+    assert!(!health.is_fresh());
+    assert!(health.pyth_usable);
+    assert!(health.price_in_bound());
+    assert!(!health.feasible);
+}
+
+#[test]
+fn test_oracle_health_outside_bound() {
+    let mut fixture = MarketFixture::sol_usdc();
+    // pyth 2% above the wooracle price, bound is 1%
+    fixture.token_a.price_update.price_message.price = 15_300_000_000;
+    let health = sol_health(&fixture.woofi_swap().unwrap());
+
+    assert_eq!(health.clo_price, 15_300_000_000);
+    assert_eq!(health.lower_bound, 15_147_000_000);
+    assert!(!health.price_in_bound());
+    assert!(!health.feasible);
+}
+
+#[test]
+fn test_oracle_health_kept_when_update_fails() {
+    // pyth updates older than maximum_age make update fail
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.advance_clock(90);
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+    assert!(woofi_swap.update(&fixture.account_map()).is_err());
+
+    let health = sol_health(&woofi_swap);
+    assert_eq!(health.pyth_age, 90);
+    assert_eq!(health.quote_pyth_age, 90);
+    assert!(!health.pyth_usable);
+    assert!(!health.feasible);
+
+    // out of range
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.wooracle.range_max = 14_000_000_000;
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+    assert!(woofi_swap.update(&fixture.account_map()).is_err());
+
+    let health = sol_health(&woofi_swap);
+    assert!(!health.price_in_range());
+    assert_eq!(health.range_max_margin(), -1_000_000_000);
+    assert_eq!(health.range_min_margin(), 13_500_000_000);
+    assert!(!health.feasible);
+}
+
+#[test]
+fn test_state_follows_oracle_health() {
+    let mut stale = MarketFixture::sol_usdc();
+    stale.advance_clock(200);
+    stale.refresh_price_updates();
+
+    for fixture in [MarketFixture::sol_usdc(), stale] {
+        let woofi_swap = fixture.woofi_swap().unwrap();
+        let [(_, health_a), (_, health_b)] = woofi_swap.oracle_health().unwrap();
+
+        for (health, state) in [
+            (health_a, woofi_swap.state_a.unwrap()),
+            (health_b, woofi_swap.state_b.unwrap()),
+        ] {
+            assert_eq!(state.feasible_out, health.feasible);
+            assert_eq!(
+                state.price_out,
+                if health.feasible { health.wo_price } else { 0 }
+            );
+        }
+    }
+}
+
+#[test]
+fn test_oracle_health_before_update() {
+    let fixture = MarketFixture::sol_usdc();
+    let woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+
+    assert!(woofi_swap.oracle_health().is_err());
+}
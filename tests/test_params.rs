@@ -0,0 +1,205 @@
+use jupiter_amm_interface::{Amm, KeyedAccount};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use woofi_jupiter::{
+    cluster::{Cluster, DEVNET_USDC},
+    errors::ParamsError,
+    params::WoofiParams,
+    testing::{sell_sol, MarketFixture, TokenSpec},
+    util::USDC,
+    WoofiSwap,
+};
+
+fn keyed_account(fixture: &MarketFixture, params: Value) -> KeyedAccount {
+    KeyedAccount {
+        params: Some(params),
+        ..fixture.keyed_account()
+    }
+}
+
+fn from_params(fixture: &MarketFixture, params: Value) -> anyhow::Result<WoofiSwap> {
+    WoofiSwap::from_keyed_account(&keyed_account(fixture, params), &fixture.amm_context())
+}
+
+fn params_error(error: anyhow::Error) -> ParamsError {
+    error.downcast::<ParamsError>().unwrap()
+}
+
+#[test]
+fn test_parse_defaults() {
+    assert_eq!(WoofiParams::parse(None).unwrap(), WoofiParams::default());
+    assert_eq!(
+        WoofiParams::parse(Some(&Value::Null)).unwrap(),
+        WoofiParams::default()
+    );
+    assert_eq!(
+        WoofiParams::parse(Some(&json!({}))).unwrap(),
+        WoofiParams::default()
+    );
+}
+
+#[test]
+fn test_parse_all_params() {
+    let rebate_to = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let params = json!({
+        "rebateTo": rebate_to.to_string(),
+        "label": "WooFi SOL/USDC",
+        "programId": program_id.to_string(),
+        "quoteMint": USDC.to_string(),
+        "maxStalenessSeconds": 30,
+        "maxConfRatio": 10_000_000_000_000_000_u64,
+    });
+
+    assert_eq!(
+        WoofiParams::parse(Some(&params)).unwrap(),
+        WoofiParams {
+            rebate_to: Some(rebate_to),
+            label: Some("WooFi SOL/USDC".into()),
+            program_id: Some(program_id),
+            quote_mint: Some(USDC),
+            max_staleness: Some(30),
+            max_conf_ratio: Some(10_000_000_000_000_000),
+        }
+    );
+}
+
+#[test]
+fn test_parse_validation_errors() {
+    assert_eq!(
+        WoofiParams::parse(Some(&json!([]))).unwrap_err(),
+        ParamsError::NotAnObject
+    );
+    assert_eq!(
+        WoofiParams::parse(Some(&json!({ "rebate_to": USDC.to_string() }))).unwrap_err(),
+        ParamsError::UnknownKey("rebate_to".into())
+    );
+
+    for (params, key) in [
+        (json!({ "rebateTo": "not a pubkey" }), "rebateTo"),
+        (json!({ "programId": 7 }), "programId"),
+        (json!({ "quoteMint": null }), "quoteMint"),
+        (json!({ "label": "" }), "label"),
+        (json!({ "maxStalenessSeconds": -1 }), "maxStalenessSeconds"),
+        (
+            json!({ "maxStalenessSeconds": "30" }),
+            "maxStalenessSeconds",
+        ),
+        (json!({ "maxConfRatio": 1e16 }), "maxConfRatio"),
+    ] {
+        match WoofiParams::parse(Some(&params)).unwrap_err() {
+            ParamsError::InvalidValue {
+                key: invalid_key, ..
+            } => assert_eq!(invalid_key, key),
+            other => panic!("unexpected error {:?} for {}", other, params),
+        }
+    }
+}
+
+#[test]
+fn test_from_keyed_account_applies_params() {
+    let fixture = MarketFixture::sol_usdc();
+    let rebate_to = Pubkey::new_unique();
+    let woofi_swap = from_params(
+        &fixture,
+        json!({
+            "rebateTo": rebate_to.to_string(),
+            "label": "WooFi SOL/USDC",
+            "quoteMint": USDC.to_string(),
+            "maxStalenessSeconds": 30,
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(woofi_swap.label(), "WooFi SOL/USDC");
+    assert_eq!(woofi_swap.rebate_to, Some(rebate_to));
+    assert_eq!(woofi_swap.oracle_guard_a.max_staleness, Some(30));
+    assert_eq!(woofi_swap.oracle_guard_b.max_staleness, Some(30));
+    assert_eq!(woofi_swap.oracle_guard_a.max_conf_ratio, None);
+    assert_eq!(woofi_swap.cluster, Some(Cluster::MainnetBeta));
+
+    let woofi_swap = from_params(
+        &fixture,
+        json!({ "programId": woofi_jupiter::id().to_string() }),
+    )
+    .unwrap();
+    assert_eq!(woofi_swap.cluster, Some(Cluster::MainnetBeta));
+}
+
+#[test]
+fn test_from_keyed_account_rejects_invalid_params() {
+    let fixture = MarketFixture::sol_usdc();
+
+    let error = from_params(&fixture, json!({ "label": 1 })).unwrap_err();
+    assert!(matches!(
+        params_error(error),
+        ParamsError::InvalidValue { key: "label", .. }
+    ));
+
+    let other_program = Pubkey::new_unique();
+    let error =
+        from_params(&fixture, json!({ "programId": other_program.to_string() })).unwrap_err();
+    assert_eq!(
+        params_error(error),
+        ParamsError::ProgramIdMismatch {
+            program_id: other_program,
+            owner: woofi_jupiter::id(),
+        }
+    );
+
+    let error = from_params(&fixture, json!({ "quoteMint": DEVNET_USDC.to_string() })).unwrap_err();
+    assert_eq!(
+        params_error(error),
+        ParamsError::QuoteMintMismatch {
+            quote_mint: DEVNET_USDC,
+            pool_quote_mint: USDC,
+        }
+    );
+}
+
+#[test]
+fn test_program_id_param_allows_custom_deployment() {
+    let program_id = Pubkey::new_unique();
+    let fixture = MarketFixture::new(
+        program_id,
+        TokenSpec::sol(),
+        TokenSpec::usdc(),
+        TokenSpec::usdc(),
+    );
+
+    assert!(
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).is_err()
+    );
+
+    let mut woofi_swap =
+        from_params(&fixture, json!({ "programId": program_id.to_string() })).unwrap();
+    assert_eq!(woofi_swap.program_id(), program_id);
+    assert_eq!(woofi_swap.cluster, None);
+
+    woofi_swap.update(&fixture.account_map()).unwrap();
+    assert_eq!(
+        woofi_swap.quote(&sell_sol()).unwrap().out_amount,
+        149_947_480
+    );
+}
+
+#[test]
+fn test_max_staleness_param() {
+    let mut fixture = MarketFixture::sol_usdc();
+    let mut default_swap = fixture.woofi_swap().unwrap();
+    let mut strict_swap = from_params(&fixture, json!({ "maxStalenessSeconds": 20 })).unwrap();
+
+    // 30s is within the wooracle stale_duration (120s) but past the param
+    fixture.advance_clock(30);
+    fixture.refresh_price_updates();
+    default_swap.update(&fixture.account_map()).unwrap();
+    strict_swap.update(&fixture.account_map()).unwrap();
+
+    assert!(default_swap.quote(&sell_sol()).is_ok());
+    assert!(strict_swap.quote(&sell_sol()).is_err());
+
+    let health = strict_swap.oracle_health_a.unwrap();
+    assert_eq!(health.stale_duration, 20);
+    assert_eq!(health.maximum_age, 20);
+    assert!(!health.is_fresh());
+}
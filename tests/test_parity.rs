@@ -0,0 +1,542 @@
+use std::{collections::HashSet, fs, path::PathBuf, time::Duration};
+
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use anyhow::{Context, Error};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use jupiter_amm_interface::{AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, SwapMode};
+use num_bigint::BigUint;
+use serde_json::{json, Value};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_request::RpcRequest,
+};
+use solana_sdk::{
+    clock::Clock, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+};
+use woofi_jupiter::{
+    discovery::discover_pools,
+    state::{SwapEvent, Wooracle},
+    testing::{
+        mint_label, quote_params, sell_sol, swap_events, MarketFixture, MarketSnapshot,
+        RecordedSwap, TokenSpec, FIXTURE_WOO, SOURCE_RPC,
+    },
+    util::{SOL, USDC},
+    WoofiSwap,
+};
+
+const ONE_E18: u128 = 1_000_000_000_000_000_000;
+
+// WOO/USDC with a price that does not divide evenly by price_dec, so every floor step matters
+fn woo_usdc_uneven_price() -> MarketFixture {
+    let woo = TokenSpec {
+        price: 25_012_345,
+        ..TokenSpec::woo()
+    };
+    MarketFixture::new(
+        woofi_jupiter::id(),
+        woo,
+        TokenSpec::usdc(),
+        TokenSpec::usdc(),
+    )
+}
+
+fn big(value: u128) -> BigUint {
+    BigUint::from(value)
+}
+
+fn small(value: BigUint) -> u128 {
+    u128::try_from(value).unwrap()
+}
+
+fn pow10(exp: u8) -> BigUint {
+    BigUint::from(10_u32).pow(exp as u32)
+}
+
+// The sell base steps of the swap_math rounding contract, one floor per step, in arbitrary precision
+fn reference_sell_base(base_amount: u128, oracle: &Wooracle) -> u128 {
+    let (price_dec, quote_dec, base_dec) = (
+        pow10(oracle.price_decimals),
+        pow10(oracle.quote_decimals),
+        pow10(oracle.base_decimals),
+    );
+    let a = big(base_amount) * big(oracle.price) / price_dec;
+    let gamma = &a * big(oracle.coeff as u128) / &base_dec;
+    let c = a * (big(ONE_E18) - gamma - big(oracle.spread as u128)) / big(ONE_E18);
+    small(c * quote_dec / base_dec)
+}
+
+// The sell quote steps of the swap_math rounding contract, one floor per step, in arbitrary precision
+fn reference_sell_quote(quote_amount: u128, oracle: &Wooracle) -> u128 {
+    let (price_dec, quote_dec, base_dec) = (
+        pow10(oracle.price_decimals),
+        pow10(oracle.quote_decimals),
+        pow10(oracle.base_decimals),
+    );
+    let gamma = big(quote_amount) * big(oracle.coeff as u128) / &quote_dec;
+    let b = big(quote_amount) * base_dec * price_dec / big(oracle.price);
+    let d = b * (big(ONE_E18) - gamma - big(oracle.spread as u128)) / big(ONE_E18);
+    small(d / quote_dec)
+}
+
+// WooPPV2's association order for sell base, which the rounding contract does not follow
+fn solidity_sell_base(base_amount: u128, oracle: &Wooracle) -> u128 {
+    let (price_dec, quote_dec, base_dec) = (
+        pow10(oracle.price_decimals),
+        pow10(oracle.quote_decimals),
+        pow10(oracle.base_decimals),
+    );
+    let notional = big(base_amount) * big(oracle.price);
+    let gamma = &notional * big(oracle.coeff as u128) / &price_dec / &base_dec;
+    let a = notional * quote_dec / price_dec;
+    small(a * (big(ONE_E18) - gamma - big(oracle.spread as u128)) / big(ONE_E18) / base_dec)
+}
+
+fn swap_fee(usdc_amount: u128, fee_rate: u16) -> u128 {
+    (usdc_amount * fee_rate as u128).div_ceil(100_000)
+}
+
+// (out_amount, fee_amount) the rounding contract gives for an ExactIn swap, worked from the wooracle
+// accounts the market read
+fn reference_swap(
+    woofi_swap: &WoofiSwap,
+    account_map: &AccountMap,
+    in_amount: u64,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+) -> (u64, u64) {
+    let leg = |mint: Pubkey| {
+        let (wooracle, woopool) = if mint == woofi_swap.token_a_mint {
+            (woofi_swap.token_a_wooracle, woofi_swap.woopool_a.as_ref())
+        } else {
+            (woofi_swap.token_b_wooracle, woofi_swap.woopool_b.as_ref())
+        };
+        let wooracle =
+            Wooracle::try_deserialize(&mut account_map[&wooracle].data.as_slice()).unwrap();
+        (wooracle, woopool.unwrap().fee_rate)
+    };
+    let ((from_oracle, from_fee_rate), (to_oracle, to_fee_rate)) =
+        (leg(input_mint), leg(output_mint));
+    let usdc = woofi_swap.usdc_mint;
+    let fee_rate = if input_mint == usdc {
+        to_fee_rate
+    } else if output_mint == usdc {
+        from_fee_rate
+    } else {
+        from_fee_rate.max(to_fee_rate)
+    };
+
+    let usdc_amount = if input_mint == usdc {
+        in_amount as u128
+    } else {
+        reference_sell_base(in_amount as u128, &from_oracle)
+    };
+    let fee = swap_fee(usdc_amount, fee_rate);
+    let out_amount = if output_mint == usdc {
+        usdc_amount - fee
+    } else {
+        reference_sell_quote(usdc_amount - fee, &to_oracle)
+    };
+
+    (out_amount as u64, fee as u64)
+}
+
+fn swaps_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/swaps")
+}
+
+fn recorded_swap_paths() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(swaps_dir()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+#[ignore = "no mainnet swaps are recorded yet; run record_mainnet_swaps to fill tests/fixtures/swaps"]
+fn test_recorded_swaps_match_to_the_lamport() {
+    let paths = recorded_swap_paths();
+    assert!(
+        !paths.is_empty(),
+        "no recorded swaps in {}",
+        swaps_dir().display()
+    );
+
+    let mut directions = HashSet::new();
+    for path in paths {
+        let recorded = RecordedSwap::load(&path).unwrap();
+        let params = recorded.quote_params().unwrap();
+        let quote = recorded.replay().unwrap();
+
+        let case = format!("{} ({})", path.display(), recorded.signature);
+        assert_eq!(quote.in_amount, recorded.from_amount, "{}", case);
+        assert_eq!(quote.out_amount, recorded.to_amount, "{}", case);
+        assert_eq!(quote.fee_amount, recorded.swap_fee, "{}", case);
+
+        // the rounding contract itself matches the program, not only the quote
+        let woofi_swap = recorded.market.woofi_swap().unwrap();
+        assert_eq!(
+            reference_swap(
+                &woofi_swap,
+                &recorded.market.account_map().unwrap(),
+                recorded.from_amount,
+                params.input_mint,
+                params.output_mint
+            ),
+            (recorded.to_amount, recorded.swap_fee),
+            "{}",
+            case
+        );
+        directions.insert((params.input_mint, params.output_mint));
+    }
+
+    assert!(
+        directions.contains(&(SOL, USDC)),
+        "no SOL -> USDC swap recorded"
+    );
+    assert!(
+        directions.contains(&(USDC, SOL)),
+        "no USDC -> SOL swap recorded"
+    );
+}
+
+#[test]
+fn test_recorded_swap_round_trip() {
+    let fixture = MarketFixture::sol_usdc();
+    // recorded swaps only come from rpc captures; fixture accounts stand in for one here and are
+    // never written to tests/fixtures
+    let market = MarketSnapshot::capture(
+        "SOL/USDC",
+        SOURCE_RPC,
+        &fixture.keyed_account(),
+        &fixture.account_map(),
+        &[],
+    )
+    .unwrap();
+    let quote = fixture.woofi_swap().unwrap().quote(&sell_sol()).unwrap();
+
+    let event = SwapEvent {
+        sender: Pubkey::new_unique(),
+        from_token_mint: SOL,
+        to_token_mint: USDC,
+        from_amount: quote.in_amount as u128,
+        to_amount: quote.out_amount as u128,
+        from_account: Pubkey::new_unique(),
+        to_account: Pubkey::new_unique(),
+        rebate_to: Pubkey::new_unique(),
+        swap_vol: (quote.out_amount + quote.fee_amount) as u128,
+        swap_fee: quote.fee_amount as u128,
+    };
+    let mut data = SwapEvent::DISCRIMINATOR.to_vec();
+    event.serialize(&mut data).unwrap();
+    let logs = vec![
+        format!("Program {} invoke [2]", woofi_jupiter::id()),
+        format!("Program data: {}", STANDARD.encode([7_u8; 40])),
+        format!("Program data: {}", STANDARD.encode(&data)),
+    ];
+    let events = swap_events(&logs);
+    assert_eq!(events, vec![event]);
+
+    let recorded =
+        RecordedSwap::new("fixture", fixture.clock.slot + 1, market, &events[0]).unwrap();
+    let path = std::env::temp_dir().join("woofi_recorded_swap_round_trip.json");
+    recorded.save(&path).unwrap();
+    let loaded = RecordedSwap::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.quote_params().unwrap().amount, 1_000_000_000);
+    let replayed = loaded.replay().unwrap();
+    assert_eq!(
+        (replayed.in_amount, replayed.out_amount, replayed.fee_amount),
+        (loaded.from_amount, loaded.to_amount, loaded.swap_fee)
+    );
+}
+
+// Checks the u128 and 256-bit arithmetic in swap_math against the same steps in arbitrary
+// precision; whether those steps are the program's is up to the recorded swaps above
+#[test]
+fn test_quotes_match_big_integer_rounding_contract() {
+    let markets = [
+        (MarketFixture::sol_usdc(), SOL, USDC),
+        (MarketFixture::sol_woo(), SOL, FIXTURE_WOO),
+        (woo_usdc_uneven_price(), FIXTURE_WOO, USDC),
+    ];
+
+    for (fixture, mint_a, mint_b) in markets {
+        let woofi_swap = fixture.woofi_swap().unwrap();
+        let account_map = fixture.account_map();
+
+        for (input_mint, output_mint) in [(mint_a, mint_b), (mint_b, mint_a)] {
+            let mut in_amount: u64 = 7;
+            while in_amount < 100_000_000_000 {
+                let quote = woofi_swap
+                    .quote(&quote_params(
+                        in_amount,
+                        input_mint,
+                        output_mint,
+                        SwapMode::ExactIn,
+                    ))
+                    .unwrap();
+                assert_eq!(
+                    (quote.out_amount, quote.fee_amount),
+                    reference_swap(
+                        &woofi_swap,
+                        &account_map,
+                        in_amount,
+                        input_mint,
+                        output_mint
+                    ),
+                    "{} {} -> {}",
+                    in_amount,
+                    input_mint,
+                    output_mint
+                );
+                in_amount = in_amount * 3 + 1;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sell_base_floors_price_before_quote_dec() {
+    let fixture = woo_usdc_uneven_price();
+    let oracle = &fixture.token_a.wooracle;
+
+    // flooring a = base * price / price_dec before scaling to quote_dec loses the last unit
+    assert_eq!(reference_sell_base(1_000_001, oracle), 250_047);
+    assert_eq!(solidity_sell_base(1_000_001, oracle), 250_048);
+
+    let quote = fixture
+        .woofi_swap()
+        .unwrap()
+        .quote(&quote_params(
+            1_000_001,
+            FIXTURE_WOO,
+            USDC,
+            SwapMode::ExactIn,
+        ))
+        .unwrap();
+    assert_eq!(quote.out_amount + quote.fee_amount, 250_047);
+}
+
+const MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
+// markets read before giving up on catching a swap that lands before anything else touches them
+const RECORD_ATTEMPTS: usize = 30;
+
+// Every account the market reads in one request, so all of them including the clock are from one slot
+async fn capture_market(
+    client: &RpcClient,
+    label: &str,
+    keyed_account: &KeyedAccount,
+) -> Result<MarketSnapshot, Error> {
+    let amm_context = AmmContext {
+        clock_ref: ClockRef::from(Clock::default()),
+    };
+    let woofi_swap = WoofiSwap::from_keyed_account(keyed_account, &amm_context)?;
+    let pubkeys = woofi_swap.get_accounts_to_update();
+    let account_map: AccountMap = pubkeys
+        .iter()
+        .zip(client.get_multiple_accounts(&pubkeys).await?)
+        .filter_map(|(key, account)| account.map(|account| (*key, account)))
+        .collect();
+    MarketSnapshot::capture(label, SOURCE_RPC, keyed_account, &account_map, &[])
+}
+
+// Oldest successful transaction on `address` after `slot`, waiting up to a minute for one to land
+async fn next_transaction(
+    client: &RpcClient,
+    address: &Pubkey,
+    slot: u64,
+) -> Result<Option<Signature>, Error> {
+    for _ in 0..30 {
+        let statuses = client.get_signatures_for_address(address).await?;
+        // newest first
+        if let Some(status) = statuses
+            .iter()
+            .rev()
+            .find(|status| status.slot > slot && status.err.is_none())
+        {
+            return Ok(Some(status.signature.parse()?));
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+    Ok(None)
+}
+
+// Whether the last transaction referencing `address` before `signature` landed by `slot`
+async fn untouched_since(
+    client: &RpcClient,
+    address: &Pubkey,
+    signature: &Signature,
+    slot: u64,
+) -> Result<bool, Error> {
+    let config = GetConfirmedSignaturesForAddress2Config {
+        before: Some(*signature),
+        until: None,
+        limit: Some(1),
+        commitment: Some(CommitmentConfig::confirmed()),
+    };
+    let previous = client
+        .get_signatures_for_address_with_config(address, config)
+        .await?;
+    Ok(previous.first().map_or(true, |status| status.slot <= slot))
+}
+
+// Change of a token account's balance in a transaction fetched with the json encoding
+fn token_balance_delta(transaction: &Value, token_account: &Pubkey) -> Option<i128> {
+    let meta = &transaction["meta"];
+    let keys: Vec<&str> = [
+        &transaction["transaction"]["message"]["accountKeys"],
+        &meta["loadedAddresses"]["writable"],
+        &meta["loadedAddresses"]["readonly"],
+    ]
+    .into_iter()
+    .filter_map(Value::as_array)
+    .flatten()
+    .filter_map(Value::as_str)
+    .collect();
+    let index = keys
+        .iter()
+        .position(|key| *key == token_account.to_string())?;
+
+    let amount = |balances: &Value| -> Option<i128> {
+        balances
+            .as_array()?
+            .iter()
+            .find(|balance| balance["accountIndex"] == json!(index))?["uiTokenAmount"]["amount"]
+            .as_str()?
+            .parse()
+            .ok()
+    };
+    Some(amount(&meta["postTokenBalances"])? - amount(&meta["preTokenBalances"])?)
+}
+
+// The transaction as a RecordedSwap on `market`, None when it is not a single swap between the
+// market's tokens or another transaction touched the swap's wooracles or woopools after `market.slot`
+async fn record_swap(
+    client: &RpcClient,
+    market: &MarketSnapshot,
+    woofi_swap: &WoofiSwap,
+    signature: &Signature,
+) -> Result<Option<RecordedSwap>, Error> {
+    let market_slot = market.slot.context("Capture without a slot")?;
+    let transaction: Value = client
+        .send(
+            RpcRequest::GetTransaction,
+            json!([
+                signature.to_string(),
+                { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }
+            ]),
+        )
+        .await?;
+    let slot = transaction["slot"]
+        .as_u64()
+        .context("Transaction without a slot")?;
+    let logs: Vec<String> = serde_json::from_value(transaction["meta"]["logMessages"].clone())?;
+    let events = swap_events(&logs);
+    let [event] = events.as_slice() else {
+        return Ok(None);
+    };
+    let Ok(pool_accounts) =
+        woofi_swap.swap_pool_accounts(&event.from_token_mint, &event.to_token_mint)
+    else {
+        return Ok(None);
+    };
+
+    for address in [
+        pool_accounts.wooracle_from,
+        pool_accounts.woopool_from,
+        pool_accounts.wooracle_to,
+        pool_accounts.woopool_to,
+        pool_accounts.woopool_quote,
+    ] {
+        if !untouched_since(client, &address, signature, market_slot).await? {
+            return Ok(None);
+        }
+    }
+
+    // SwapEvent's layout is declared on this side, so check its amounts against the vault transfers
+    assert_eq!(
+        token_balance_delta(&transaction, &pool_accounts.token_vault_from),
+        Some(event.from_amount as i128),
+        "{}",
+        signature
+    );
+    assert_eq!(
+        token_balance_delta(&transaction, &pool_accounts.token_vault_to),
+        Some(-(event.to_amount as i128)),
+        "{}",
+        signature
+    );
+
+    Ok(Some(RecordedSwap::new(
+        &signature.to_string(),
+        slot,
+        market.clone(),
+        event,
+    )?))
+}
+
+#[tokio::test]
+#[ignore = "records mainnet WooFi swaps, with the accounts they ran on, into tests/fixtures/swaps"]
+async fn record_mainnet_swaps() -> Result<(), Error> {
+    let client =
+        RpcClient::new_with_commitment(MAINNET_RPC.to_string(), CommitmentConfig::confirmed());
+    let program_id = woofi_jupiter::id();
+    let discovery = discover_pools(
+        &program_id,
+        &client.get_program_accounts(&program_id).await?,
+    );
+
+    let mut directions = HashSet::new();
+    for pool in &discovery.pools {
+        let keyed_account = pool.keyed_account();
+        let (mint_a, mint_b) = (pool.pool.token_mint_a, pool.pool.token_mint_b);
+        let label = format!("{}/{}", mint_label(&mint_a), mint_label(&mint_b));
+
+        // one swap each way
+        for _ in 0..RECORD_ATTEMPTS {
+            if directions.contains(&(mint_a, mint_b)) && directions.contains(&(mint_b, mint_a)) {
+                break;
+            }
+            let market = capture_market(&client, &label, &keyed_account).await?;
+            let woofi_swap = market.woofi_swap()?;
+            let market_slot = market.slot.context("Capture without a slot")?;
+            let Some(signature) =
+                next_transaction(&client, &woofi_swap.token_a_woopool, market_slot).await?
+            else {
+                continue;
+            };
+            let Some(recorded) = record_swap(&client, &market, &woofi_swap, &signature).await?
+            else {
+                continue;
+            };
+
+            let params = recorded.quote_params()?;
+            if !directions.insert((params.input_mint, params.output_mint)) {
+                continue;
+            }
+            let file_name = format!(
+                "{}_{}_{}.json",
+                mint_label(&params.input_mint),
+                mint_label(&params.output_mint),
+                recorded.slot
+            );
+            recorded.save(swaps_dir().join(file_name.to_lowercase()))?;
+        }
+    }
+
+    assert!(
+        directions.contains(&(SOL, USDC)),
+        "no SOL -> USDC swap recorded"
+    );
+    assert!(
+        directions.contains(&(USDC, SOL)),
+        "no USDC -> SOL swap recorded"
+    );
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use jupiter_amm_interface::{Amm, SwapMode};
+use rust_decimal::Decimal;
+use woofi_jupiter::{
+    testing::{quote_params, sell_sol, MarketFixture, FIXTURE_WOO},
+    util::{PriceImpact, SOL, USDC},
+};
+
+#[test]
+fn test_price_impact_sell_base() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+    let price_impact = woofi_swap.price_impact(&sell_sol()).unwrap();
+
+    // 150 * (1 - 1bps spread) * (1 - 2.5bps fee)
+    assert_eq!(price_impact.marginal_price, Decimal::new(14_994_750_375, 8));
+    // matches the quote: 149.94748 USDC for 1 SOL
+    assert_eq!(price_impact.execution_price, Decimal::new(14_994_748, 5));
+    assert!(price_impact.price_impact_bps > Decimal::ZERO);
+    assert!(price_impact.price_impact_bps < Decimal::new(1, 2));
+
+    // selling SOL moves the SOL price down
+    assert!(price_impact.post_trade_price < price_impact.marginal_price);
+}
+
+#[test]
+fn test_price_impact_grows_with_size() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    let mut previous = Decimal::ZERO;
+    for amount in [1_000_000_000, 100_000_000_000, 1_000_000_000_000] {
+        let price_impact = woofi_swap
+            .price_impact(&quote_params(amount, SOL, USDC, SwapMode::ExactIn))
+            .unwrap();
+        assert!(price_impact.price_impact_bps > previous);
+        previous = price_impact.price_impact_bps;
+    }
+}
+
+#[test]
+fn test_price_impact_sell_quote() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+    let price_impact = woofi_swap
+        .price_impact(&quote_params(150_000_000, USDC, SOL, SwapMode::ExactIn))
+        .unwrap();
+
+    // SOL per USDC, just under 1/150
+    assert!(price_impact.marginal_price < Decimal::ONE / Decimal::from(150));
+    assert!(price_impact.price_impact_bps > Decimal::ZERO);
+    // buying SOL moves the SOL price up, so each USDC buys less
+    assert!(price_impact.post_trade_price < price_impact.marginal_price);
+}
+
+#[test]
+fn test_price_impact_base_to_base() {
+    let woofi_swap = MarketFixture::sol_woo().woofi_swap().unwrap();
+    let price_impact = woofi_swap
+        .price_impact(&quote_params(
+            1_000_000_000,
+            SOL,
+            FIXTURE_WOO,
+            SwapMode::ExactIn,
+        ))
+        .unwrap();
+
+    // 150 / 0.25 before spreads and fee
+    assert!(price_impact.marginal_price < Decimal::from(600));
+    assert!(price_impact.marginal_price > Decimal::from(599));
+    assert!(price_impact.price_impact_bps > Decimal::ZERO);
+    assert!(price_impact.post_trade_price < price_impact.marginal_price);
+}
+
+#[test]
+fn test_price_impact_exact_out() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+    let params = quote_params(150_000_000, SOL, USDC, SwapMode::ExactOut);
+
+    let quote = woofi_swap.quote(&params).unwrap();
+    let price_impact = woofi_swap.price_impact(&params).unwrap();
+
+    let execution_price =
+        Decimal::new(quote.out_amount as i64, 6) / Decimal::new(quote.in_amount as i64, 9);
+    assert_eq!(price_impact.execution_price, execution_price);
+}
+
+#[test]
+fn test_price_impact_json() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+    let price_impact = woofi_swap.price_impact(&sell_sol()).unwrap();
+
+    let json = serde_json::to_string(&price_impact).unwrap();
+    assert_eq!(
+        serde_json::from_str::<PriceImpact>(&json).unwrap(),
+        price_impact
+    );
+}
+
+#[test]
+fn test_swap_price_impact_matches_quote() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+    let swap_result = woofi_swap.compute_swap(1_000_000_000, &SOL, &USDC).unwrap();
+
+    assert_eq!(
+        woofi_swap
+            .swap_price_impact(&swap_result, &SOL, &USDC)
+            .unwrap(),
+        woofi_swap.price_impact(&sell_sol()).unwrap()
+    );
+}
@@ -0,0 +1,151 @@
+use jupiter_amm_interface::Amm;
+use proptest::prelude::*;
+use woofi_jupiter::{
+    testing::{sell_sol, MarketFixture},
+    util::normalize_pyth_price,
+};
+
+#[test]
+fn test_normalize_pyth_price_same_exponent() {
+    // SOL $150 / USDC $1, both at -8, into 8 price decimals
+    assert_eq!(
+        normalize_pyth_price(15_000_000_000, -8, 100_000_000, -8, 8),
+        Some(15_000_000_000)
+    );
+    assert_eq!(
+        normalize_pyth_price(15_000_000_000, -8, 99_990_000, -8, 8),
+        Some(15_001_500_150)
+    );
+}
+
+#[test]
+fn test_normalize_pyth_price_mixed_exponents() {
+    let sol_usdc = Some(15_000_000_000);
+    assert_eq!(
+        normalize_pyth_price(15_000_000, -5, 100_000_000, -8, 8),
+        sol_usdc
+    );
+    assert_eq!(
+        normalize_pyth_price(15_000_000_000, -8, 1_000_000, -6, 8),
+        sol_usdc
+    );
+    assert_eq!(normalize_pyth_price(150, 0, 1, 0, 8), sol_usdc);
+    assert_eq!(normalize_pyth_price(15, 1, 10, -1, 8), sol_usdc);
+
+    // other wooracle price decimals
+    assert_eq!(
+        normalize_pyth_price(15_000_000_000, -8, 100_000_000, -8, 6),
+        Some(150_000_000)
+    );
+    assert_eq!(
+        normalize_pyth_price(15_000_000_000, -8, 100_000_000, -8, 18),
+        Some(150_000_000_000_000_000_000)
+    );
+}
+
+#[test]
+fn test_normalize_pyth_price_unusable() {
+    assert_eq!(normalize_pyth_price(0, -8, 100_000_000, -8, 8), None);
+    assert_eq!(normalize_pyth_price(15_000_000_000, -8, 0, -8, 8), None);
+    assert_eq!(
+        normalize_pyth_price(-15_000_000_000, -8, 100_000_000, -8, 8),
+        None
+    );
+    assert_eq!(
+        normalize_pyth_price(15_000_000_000, -8, -100_000_000, -8, 8),
+        None
+    );
+
+    // too large for u128, too small to register
+    assert_eq!(normalize_pyth_price(i64::MAX, 30, 1, -30, 8), None);
+    assert_eq!(normalize_pyth_price(i64::MAX, -60, 1, 0, 0), Some(0));
+}
+
+#[test]
+fn test_quote_token_exponent_does_not_misprice() {
+    let reference = MarketFixture::sol_usdc().woofi_swap().unwrap();
+
+    // the same $1 USDC published at -6 instead of -8
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_b.price_update.price_message.price = 1_000_000;
+    fixture.token_b.price_update.price_message.exponent = -6;
+    fixture.token_b.price_update.price_message.conf = 500;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    assert!(woofi_swap.state_a.unwrap().feasible_out);
+    assert_eq!(
+        woofi_swap.quote(&sell_sol()).unwrap().out_amount,
+        reference.quote(&sell_sol()).unwrap().out_amount
+    );
+}
+
+proptest! {
+    // matches the previous formula whenever its assumptions held
+    #[test]
+    fn prop_same_exponent_matches_legacy(
+        base_price in 1..1_000_000_000_000_i64,
+        quote_price in 1..1_000_000_000_000_i64,
+        decimals in 0..=12_u8,
+    ) {
+        let expo = -(decimals as i32);
+        let legacy = (base_price as u128) * 10_u128.pow(decimals as u32) / (quote_price as u128);
+        prop_assert_eq!(normalize_pyth_price(base_price, expo, quote_price, expo, decimals), Some(legacy));
+    }
+
+    // publishing the same price with a different exponent gives the same result
+    #[test]
+    fn prop_exponent_rescaling_is_invariant(
+        base_price in 1..10_000_000_i64,
+        quote_price in 1..10_000_000_i64,
+        base_expo in -12..=4_i32,
+        quote_expo in -12..=4_i32,
+        base_shift in 0..=8_u32,
+        quote_shift in 0..=8_u32,
+        price_decimals in 0..=18_u8,
+    ) {
+        let normalized = normalize_pyth_price(base_price, base_expo, quote_price, quote_expo, price_decimals);
+        let rescaled = normalize_pyth_price(
+            base_price * 10_i64.pow(base_shift),
+            base_expo - base_shift as i32,
+            quote_price * 10_i64.pow(quote_shift),
+            quote_expo - quote_shift as i32,
+            price_decimals,
+        );
+        // the larger intermediate of either form may not fit in u128
+        if let (Some(normalized), Some(rescaled)) = (normalized, rescaled) {
+            prop_assert_eq!(normalized, rescaled);
+        }
+    }
+
+    // one more price decimal is the same price with one more digit, rounded down
+    #[test]
+    fn prop_price_decimals_add_one_digit(
+        base_price in 1..i64::MAX,
+        quote_price in 1..i64::MAX,
+        base_expo in -18..=6_i32,
+        quote_expo in -18..=6_i32,
+        price_decimals in 0..18_u8,
+    ) {
+        let coarse = normalize_pyth_price(base_price, base_expo, quote_price, quote_expo, price_decimals);
+        let fine = normalize_pyth_price(base_price, base_expo, quote_price, quote_expo, price_decimals + 1);
+        if let (Some(coarse), Some(fine)) = (coarse, fine) {
+            prop_assert_eq!(fine / 10, coarse);
+        }
+    }
+
+    // a larger base price never normalizes lower
+    #[test]
+    fn prop_monotonic_in_base_price(
+        base_price in 1..i64::MAX - 1,
+        quote_price in 1..i64::MAX,
+        base_expo in -18..=6_i32,
+        quote_expo in -18..=6_i32,
+        price_decimals in 0..=18_u8,
+    ) {
+        let lower = normalize_pyth_price(base_price, base_expo, quote_price, quote_expo, price_decimals);
+        let higher = normalize_pyth_price(base_price + 1, base_expo, quote_price, quote_expo, price_decimals);
+        if let (Some(lower), Some(higher)) = (lower, higher) {
+            prop_assert!(lower <= higher);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use anyhow::Error;
+use jupiter_amm_interface::{Amm, SwapMode};
+use solana_sdk::pubkey::Pubkey;
+use woofi_jupiter::{
+    errors::{OracleFailure, QuoteError},
+    testing::{quote_params, sell_sol, MarketFixture, FIXTURE_WOO},
+    util::{SOL, USDC},
+    WoofiSwap,
+};
+
+fn quote_error(error: Error) -> QuoteError {
+    *error
+        .downcast_ref::<QuoteError>()
+        .unwrap_or_else(|| panic!("not a QuoteError: {:?}", error))
+}
+
+fn sell_sol_error(fixture: &MarketFixture) -> QuoteError {
+    quote_error(
+        fixture
+            .woofi_swap()
+            .unwrap()
+            .quote(&sell_sol())
+            .unwrap_err(),
+    )
+}
+
+#[test]
+fn test_quote_error_paused() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.wooconfig.paused = true;
+
+    assert_eq!(sell_sol_error(&fixture), QuoteError::Paused);
+}
+
+#[test]
+fn test_quote_error_oracle_not_feasible() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.advance_clock(200);
+    fixture.refresh_price_updates();
+
+    assert_eq!(
+        sell_sol_error(&fixture),
+        QuoteError::OracleNotFeasible {
+            mint: SOL,
+            reason: Some(OracleFailure::Stale),
+        }
+    );
+}
+
+#[test]
+fn test_quote_error_pyth_unusable() {
+    // pyth updates older than maximum_age fail the update, as get_price reverts on-chain
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.advance_clock(90);
+
+    assert_eq!(
+        quote_error(fixture.woofi_swap().err().unwrap()),
+        QuoteError::OracleNotFeasible {
+            mint: SOL,
+            reason: Some(OracleFailure::PythUnusable),
+        }
+    );
+}
+
+#[test]
+fn test_quote_error_invalid_mint() {
+    let woofi_swap = MarketFixture::sol_usdc().woofi_swap().unwrap();
+    let other = Pubkey::new_unique();
+
+    for (input_mint, output_mint, mint) in [
+        (other, USDC, other),
+        (SOL, other, other),
+        (SOL, SOL, SOL),
+        (USDC, USDC, USDC),
+    ] {
+        for swap_mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+            let error = woofi_swap
+                .quote(&quote_params(1_000_000, input_mint, output_mint, swap_mode))
+                .unwrap_err();
+            assert_eq!(quote_error(error), QuoteError::InvalidMint { mint });
+        }
+    }
+}
+
+#[test]
+fn test_quote_error_price_out_of_range() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.wooracle.range_max = 14_000_000_000;
+
+    let error = quote_error(fixture.woofi_swap().err().unwrap());
+    assert_eq!(
+        error,
+        QuoteError::PriceOutOfRange {
+            price: 15_000_000_000,
+            range_min: 1_500_000_000,
+            range_max: 14_000_000_000,
+        }
+    );
+}
+
+#[test]
+fn test_quote_error_max_notional() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.max_notional_swap = 100_000_000;
+
+    assert_eq!(
+        sell_sol_error(&fixture),
+        QuoteError::MaxNotionalExceeded {
+            notional: 150_000_000,
+            max_notional_swap: 100_000_000,
+        }
+    );
+}
+
+#[test]
+fn test_quote_error_gamma() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.max_gamma = 100_000_000_000;
+
+    assert_eq!(
+        sell_sol_error(&fixture),
+        QuoteError::GammaExceeded {
+            gamma: 150_000_000_000,
+            max_gamma: 100_000_000_000,
+        }
+    );
+}
+
+#[test]
+fn test_quote_error_balance_cap() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.cap_bal = 10_000_000_000_000;
+
+    assert_eq!(
+        sell_sol_error(&fixture),
+        QuoteError::BalanceCapExceeded {
+            balance_after: 10_001_000_000_000,
+            cap_bal: 10_000_000_000_000,
+        }
+    );
+}
+
+#[test]
+fn test_quote_error_insufficient_reserves() {
+    let mut fixture = MarketFixture::sol_usdc();
+    // USDC is token b of this market
+    fixture.token_b.vault_amount = 100_000_000;
+
+    match sell_sol_error(&fixture) {
+        QuoteError::InsufficientQuoteReserves {
+            required,
+            available,
+        } => {
+            assert_eq!(available, 100_000_000);
+            assert!(required > available);
+        }
+        error => panic!("unexpected {:?}", error),
+    }
+}
+
+#[test]
+fn test_quote_error_exact_out_limit() {
+    let fixture = MarketFixture::sol_usdc();
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    // 3M USDC notional against a 1M max_notional_swap
+    let error = woofi_swap
+        .quote(&quote_params(
+            20_000_000_000_000,
+            USDC,
+            SOL,
+            SwapMode::ExactOut,
+        ))
+        .unwrap_err();
+    assert!(matches!(
+        quote_error(error),
+        QuoteError::MaxNotionalExceeded { .. }
+    ));
+}
+
+#[test]
+fn test_quote_error_uninitialized() {
+    let fixture = MarketFixture::sol_usdc();
+    let woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+
+    assert_eq!(
+        quote_error(woofi_swap.quote(&sell_sol()).unwrap_err()),
+        QuoteError::Uninitialized("wooconfig")
+    );
+}
+
+#[test]
+fn test_quote_error_below_min_swap_amount() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.min_swap_amount = 1_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    let error = woofi_swap
+        .quote(&quote_params(1_000_000, SOL, USDC, SwapMode::ExactIn))
+        .unwrap_err();
+    assert_eq!(
+        quote_error(error),
+        QuoteError::BelowMinSwapAmount {
+            amount: 1_000_000,
+            min_swap_amount: 1_000_000,
+        }
+    );
+    assert!(woofi_swap
+        .quote(&quote_params(1_000_001, SOL, USDC, SwapMode::ExactIn))
+        .is_ok());
+
+    // zero is never a valid route
+    let error = woofi_swap
+        .quote(&quote_params(0, SOL, USDC, SwapMode::ExactIn))
+        .unwrap_err();
+    assert!(matches!(
+        quote_error(error),
+        QuoteError::BelowMinSwapAmount { .. }
+    ));
+}
+
+#[test]
+fn test_quote_error_below_min_swap_amount_quote_token() {
+    let mut fixture = MarketFixture::sol_usdc();
+    // USDC is token b of this market
+    fixture.token_b.woopool.min_swap_amount = 1_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    let error = woofi_swap
+        .quote(&quote_params(1_000_000, USDC, SOL, SwapMode::ExactIn))
+        .unwrap_err();
+    assert_eq!(
+        quote_error(error),
+        QuoteError::BelowMinSwapAmount {
+            amount: 1_000_000,
+            min_swap_amount: 1_000_000,
+        }
+    );
+    assert!(woofi_swap
+        .quote(&quote_params(1_000_001, USDC, SOL, SwapMode::ExactIn))
+        .is_ok());
+}
+
+#[test]
+fn test_quote_error_below_min_swap_amount_exact_out() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.min_swap_amount = 1_000_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    // 1 USDC needs far less than 1 SOL
+    let error = woofi_swap
+        .quote(&quote_params(1_000_000, SOL, USDC, SwapMode::ExactOut))
+        .unwrap_err();
+    assert!(matches!(
+        quote_error(error),
+        QuoteError::BelowMinSwapAmount {
+            min_swap_amount: 1_000_000_000,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_quote_error_below_min_swap_amount_base_to_base() {
+    let mut fixture = MarketFixture::sol_woo();
+    fixture.quote.woopool.min_swap_amount = 1_000_000_000;
+    let woofi_swap = fixture.woofi_swap().unwrap();
+
+    // 1 SOL is about 150 USDC through the quote pool
+    match quote_error(
+        woofi_swap
+            .quote(&quote_params(
+                1_000_000_000,
+                SOL,
+                FIXTURE_WOO,
+                SwapMode::ExactIn,
+            ))
+            .unwrap_err(),
+    ) {
+        QuoteError::BelowMinSwapAmount {
+            amount,
+            min_swap_amount,
+        } => {
+            assert!(amount < min_swap_amount);
+            assert_eq!(min_swap_amount, 1_000_000_000);
+        }
+        error => panic!("unexpected {:?}", error),
+    }
+
+    assert!(woofi_swap
+        .quote(&quote_params(
+            10_000_000_000,
+            SOL,
+            FIXTURE_WOO,
+            SwapMode::ExactIn
+        ))
+        .is_ok());
+}
@@ -0,0 +1,189 @@
+use jupiter_amm_interface::{Amm, SwapMode};
+use woofi_jupiter::{
+    errors::{OracleFailure, QuoteError},
+    testing::{quote_params, sell_sol, MarketFixture, FIXTURE_WOO},
+    util::{SOL, USDC},
+    WoofiSwap,
+};
+
+fn assert_same_pool(simulated: &WoofiSwap, rebuilt: &WoofiSwap) {
+    assert_eq!(simulated.token_a_balance, rebuilt.token_a_balance);
+    assert_eq!(simulated.token_b_balance, rebuilt.token_b_balance);
+    assert_eq!(simulated.usdc_balance, rebuilt.usdc_balance);
+    assert_eq!(
+        simulated.state_a.unwrap().price_out,
+        rebuilt.state_a.unwrap().price_out
+    );
+    assert_eq!(
+        simulated.state_b.unwrap().price_out,
+        rebuilt.state_b.unwrap().price_out
+    );
+    assert_eq!(simulated.oracle_health_a, rebuilt.oracle_health_a);
+    assert_eq!(simulated.oracle_health_b, rebuilt.oracle_health_b);
+    assert_eq!(
+        simulated.woopool_quote.as_ref().unwrap().unclaimed_fee,
+        rebuilt.woopool_quote.as_ref().unwrap().unclaimed_fee
+    );
+
+    let (a, b) = (simulated.token_a_mint, simulated.token_b_mint);
+    for params in [
+        quote_params(2_000_000_000, a, b, SwapMode::ExactIn),
+        quote_params(300_000_000, b, a, SwapMode::ExactIn),
+    ] {
+        let simulated_quote = simulated.quote(&params).unwrap();
+        let rebuilt_quote = rebuilt.quote(&params).unwrap();
+        assert_eq!(simulated_quote.in_amount, rebuilt_quote.in_amount);
+        assert_eq!(simulated_quote.out_amount, rebuilt_quote.out_amount);
+        assert_eq!(simulated_quote.fee_amount, rebuilt_quote.fee_amount);
+    }
+}
+
+#[test]
+fn test_apply_swap_sell_base() {
+    let fixture = MarketFixture::sol_usdc();
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+    let params = sell_sol();
+
+    let quote = woofi_swap.quote(&params).unwrap();
+    let applied = woofi_swap.apply_swap(&params).unwrap();
+    assert_eq!(applied.out_amount, quote.out_amount);
+    assert_eq!(applied.fee_amount, 37_497);
+
+    assert_eq!(woofi_swap.token_a_balance, Some(10_001_000_000_000));
+    // 149_947_480 out plus the 37_497 fee booked as unclaimed
+    assert_eq!(
+        woofi_swap.usdc_balance,
+        Some(2_000_000_000_000 - 149_984_977)
+    );
+    assert_eq!(woofi_swap.token_b_balance, woofi_swap.usdc_balance);
+    assert_eq!(
+        woofi_swap.woopool_quote.as_ref().unwrap().unclaimed_fee,
+        37_497
+    );
+    // 150 * (1 - gamma), gamma = 1.5e-7
+    assert_eq!(woofi_swap.state_a.unwrap().price_out, 14_999_997_750);
+
+    // the same swap again gets a worse price
+    assert!(woofi_swap.quote(&params).unwrap().out_amount < quote.out_amount);
+}
+
+#[test]
+fn test_apply_swap_matches_program_accounts() {
+    let mut fixture = MarketFixture::sol_usdc();
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+    woofi_swap.apply_swap(&sell_sol()).unwrap();
+    woofi_swap
+        .apply_swap(&quote_params(50_000_000, USDC, SOL, SwapMode::ExactIn))
+        .unwrap();
+
+    // the accounts after the same two swaps on chain
+    let first = MarketFixture::sol_usdc()
+        .woofi_swap()
+        .unwrap()
+        .quote(&sell_sol())
+        .unwrap();
+    fixture.token_a.vault_amount += 1_000_000_000;
+    fixture.token_b.vault_amount -= first.out_amount;
+    fixture.token_b.woopool.unclaimed_fee += first.fee_amount as u128;
+    fixture.token_a.wooracle.price = 14_999_997_750;
+    let after_first = fixture.woofi_swap().unwrap();
+
+    let second = after_first
+        .quote(&quote_params(50_000_000, USDC, SOL, SwapMode::ExactIn))
+        .unwrap();
+    fixture.token_b.vault_amount += 50_000_000;
+    fixture.token_a.vault_amount -= second.out_amount;
+    fixture.token_b.woopool.unclaimed_fee += second.fee_amount as u128;
+    fixture.token_a.wooracle.price = after_first
+        .compute_swap(50_000_000, &USDC, &SOL)
+        .unwrap()
+        .new_price_to
+        .unwrap();
+
+    assert_same_pool(&woofi_swap, &fixture.woofi_swap().unwrap());
+}
+
+#[test]
+fn test_apply_swap_base_to_base() {
+    let mut fixture = MarketFixture::sol_woo();
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+    let params = quote_params(1_000_000_000, SOL, FIXTURE_WOO, SwapMode::ExactIn);
+
+    let swap_result = woofi_swap
+        .compute_swap(1_000_000_000, &SOL, &FIXTURE_WOO)
+        .unwrap();
+    woofi_swap.apply_swap(&params).unwrap();
+
+    // USDC only pays the fee, which stays in the quote vault as unclaimed
+    fixture.token_a.vault_amount += 1_000_000_000;
+    fixture.token_b.vault_amount -= swap_result.out_amount as u64;
+    fixture.quote.woopool.unclaimed_fee += swap_result.swap_fee;
+    fixture.token_a.wooracle.price = swap_result.new_price_from.unwrap();
+    fixture.token_b.wooracle.price = swap_result.new_price_to.unwrap();
+
+    assert_same_pool(&woofi_swap, &fixture.woofi_swap().unwrap());
+}
+
+#[test]
+fn test_apply_swap_leaves_bound() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.wooracle.coeff = 100_000_000_000;
+    fixture.token_a.woopool.max_gamma = 100_000_000_000_000_000;
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+
+    // moves the SOL price 1.5%, past the 1% bound around pyth
+    woofi_swap
+        .apply_swap(&quote_params(
+            1_000_000_000_000,
+            SOL,
+            USDC,
+            SwapMode::ExactIn,
+        ))
+        .unwrap();
+
+    assert!(!woofi_swap.oracle_health_a.unwrap().feasible);
+    let error = woofi_swap.quote(&sell_sol()).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<QuoteError>(),
+        Some(&QuoteError::OracleNotFeasible {
+            mint: SOL,
+            reason: Some(OracleFailure::OutOfBound),
+        })
+    );
+}
+
+#[test]
+fn test_apply_swap_failure_keeps_state() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.token_a.woopool.max_notional_swap = 100_000_000;
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+
+    assert!(woofi_swap.apply_swap(&sell_sol()).is_err());
+    assert_eq!(woofi_swap.token_a_balance, Some(10_000_000_000_000));
+    assert_eq!(woofi_swap.state_a.unwrap().price_out, 15_000_000_000);
+}
+
+#[test]
+fn test_apply_swap_refreshes_wooracle() {
+    let mut fixture = MarketFixture::sol_usdc();
+    fixture.advance_clock(100);
+    fixture.refresh_price_updates();
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+    assert_eq!(woofi_swap.oracle_health_a.unwrap().wo_age, 100);
+
+    let swap_result = woofi_swap.compute_swap(1_000_000_000, &SOL, &USDC).unwrap();
+    woofi_swap.apply_swap(&sell_sol()).unwrap();
+
+    // posting the new price stamps the wooracle with the current clock, as the program does
+    let health = woofi_swap.oracle_health_a.unwrap();
+    assert_eq!(health.wo_age, 0);
+    assert_eq!(health.updated_at, fixture.clock.unix_timestamp);
+    assert!(health.is_fresh());
+
+    fixture.token_a.wooracle.price = swap_result.new_price_from.unwrap();
+    fixture.token_a.wooracle.updated_at = fixture.clock.unix_timestamp;
+    assert_eq!(
+        woofi_swap.oracle_health_a,
+        fixture.woofi_swap().unwrap().oracle_health_a
+    );
+}
@@ -0,0 +1,200 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Error;
+use jupiter_amm_interface::{AccountMap, Amm, AmmContext, ClockRef, SwapMode};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::clock::Clock;
+use woofi_jupiter::{
+    cluster::Cluster,
+    discovery::discover_pools,
+    testing::{mint_label, quote_params, sell_sol, MarketFixture, MarketSnapshot, SOURCE_RPC},
+    util::{SOL, USDC},
+    WoofiSwap,
+};
+
+fn snapshots_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshots")
+}
+
+fn snapshot_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(snapshots_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn test_replay_snapshots() {
+    let paths = snapshot_paths();
+    assert!(paths.len() >= 2);
+
+    for path in paths {
+        let snapshot = MarketSnapshot::load(&path).unwrap();
+        assert!(
+            !snapshot.quotes.is_empty(),
+            "{} has no recorded quotes",
+            path.display()
+        );
+
+        let replayed = snapshot.replay().unwrap();
+        assert_eq!(
+            replayed,
+            snapshot.quotes,
+            "{} quotes changed",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let fixture = MarketFixture::sol_usdc();
+    let snapshot = MarketSnapshot::capture(
+        "SOL/USDC",
+        "fixture",
+        &fixture.keyed_account(),
+        &fixture.account_map(),
+        &[
+            sell_sol(),
+            quote_params(150_000_000, USDC, SOL, SwapMode::ExactOut),
+        ],
+    )
+    .unwrap();
+
+    let path = std::env::temp_dir().join("woofi_snapshot_round_trip.json");
+    snapshot.save(&path).unwrap();
+    let loaded = MarketSnapshot::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // hand-built accounts claim no cluster or slot
+    assert_eq!(loaded.cluster, None);
+    assert_eq!(loaded.slot, None);
+    assert_eq!(loaded.pool, snapshot.pool);
+    assert_eq!(loaded.accounts, snapshot.accounts);
+    assert_eq!(
+        loaded.clock().unwrap().unix_timestamp,
+        fixture.clock.unix_timestamp
+    );
+    assert_eq!(loaded.replay().unwrap(), snapshot.quotes);
+}
+
+#[test]
+fn test_snapshot_cluster_and_slot_only_from_rpc() {
+    let fixture = MarketFixture::sol_usdc();
+    let captured = MarketSnapshot::capture(
+        "SOL/USDC",
+        SOURCE_RPC,
+        &fixture.keyed_account(),
+        &fixture.account_map(),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(captured.cluster, Some(Cluster::MainnetBeta));
+    assert_eq!(captured.slot, Some(fixture.clock.slot));
+    assert!(captured.validate().is_ok());
+
+    let mut synthetic = captured.clone();
+    synthetic.source = "synthetic".into();
+    assert!(synthetic.validate().is_err());
+
+    let mut without_slot = captured;
+    without_slot.slot = None;
+    assert!(without_slot.validate().is_err());
+}
+
+#[test]
+fn test_snapshot_rejects_duplicate_accounts() {
+    let fixture = MarketFixture::sol_usdc();
+    let mut snapshot = MarketSnapshot::capture(
+        "SOL/USDC",
+        "fixture",
+        &fixture.keyed_account(),
+        &fixture.account_map(),
+        &[sell_sol()],
+    )
+    .unwrap();
+    let mut keys: Vec<&String> = snapshot
+        .accounts
+        .iter()
+        .map(|account| &account.pubkey)
+        .collect();
+    keys.sort();
+    keys.dedup();
+    assert_eq!(keys.len(), snapshot.accounts.len());
+
+    snapshot.accounts.push(snapshot.accounts[0].clone());
+    let path = std::env::temp_dir().join("woofi_snapshot_duplicate.json");
+    snapshot.save(&path).unwrap();
+    let error = MarketSnapshot::load(&path).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert!(format!("{:#}", error).contains("Duplicate account"));
+}
+
+#[tokio::test]
+#[ignore = "captures every mainnet WooFi pool into tests/fixtures/snapshots"]
+async fn capture_mainnet_snapshots() -> Result<(), Error> {
+    let client = RpcClient::new("https://api.mainnet-beta.solana.com".to_string());
+    let program_id = woofi_jupiter::id();
+    let discovery = discover_pools(
+        &program_id,
+        &client.get_program_accounts(&program_id).await?,
+    );
+    assert!(
+        discovery.rejected.is_empty(),
+        "rejected pools: {:?}",
+        discovery.rejected
+    );
+    assert!(
+        discovery
+            .pools
+            .iter()
+            .any(|pool| pool.pool.token_mint_a == SOL && pool.pool.token_mint_b == USDC),
+        "SOL/USDC pool not found"
+    );
+    assert!(
+        discovery.pools.len() >= 2,
+        "expected SOL/USDC and at least one other pool"
+    );
+
+    for pool in &discovery.pools {
+        let keyed_account = pool.keyed_account();
+        let amm_context = AmmContext {
+            clock_ref: ClockRef::from(Clock::default()),
+        };
+        let mut woofi_swap = WoofiSwap::from_keyed_account(&keyed_account, &amm_context)?;
+        let pubkeys = woofi_swap.get_accounts_to_update();
+        // one request, so every account including the clock is read at the same slot
+        let account_map: AccountMap = pubkeys
+            .iter()
+            .zip(client.get_multiple_accounts(&pubkeys).await?)
+            .filter_map(|(key, account)| account.map(|account| (*key, account)))
+            .collect();
+        woofi_swap.update(&account_map)?;
+
+        // one whole base token and 10 quote tokens each way
+        let decimals = woofi_swap.decimals_a.unwrap();
+        let (base_unit, quote_unit) = (decimals.base_dec, decimals.quote_dec);
+        let (base, quote) = (pool.pool.token_mint_a, pool.pool.token_mint_b);
+        let label = format!("{}/{}", mint_label(&base), mint_label(&quote));
+        let snapshot = MarketSnapshot::capture(
+            &label,
+            SOURCE_RPC,
+            &keyed_account,
+            &account_map,
+            &[
+                quote_params(base_unit, base, quote, SwapMode::ExactIn),
+                quote_params(10 * quote_unit, quote, base, SwapMode::ExactIn),
+                quote_params(10 * quote_unit, base, quote, SwapMode::ExactOut),
+            ],
+        )?;
+        assert_eq!(snapshot.cluster, Some(Cluster::MainnetBeta));
+
+        let file_name = format!("{}.json", label.to_lowercase().replace('/', "_"));
+        snapshot.save(snapshots_dir().join(file_name))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use anchor_lang::{prelude::AccountMeta, AccountSerialize};
+use jupiter_amm_interface::{Amm, AmmContext, ClockRef, KeyedAccount, Swap, SwapMode, SwapParams};
+use solana_sdk::{account::Account, clock::Clock, pubkey::Pubkey};
+use woofi_jupiter::{state::WooAmmPool, WoofiSwap};
+
+fn woo_amm_pool() -> WooAmmPool {
+    WooAmmPool {
+        wooconfig: Pubkey::new_unique(),
+        wooammpool_bump: [255],
+        authority: Pubkey::new_unique(),
+        wooracle_a: Pubkey::new_unique(),
+        woopool_a: Pubkey::new_unique(),
+        feed_account_a: Pubkey::new_unique(),
+        price_update_a: Pubkey::new_unique(),
+        token_mint_a: Pubkey::new_unique(),
+        token_vault_a: Pubkey::new_unique(),
+        wooracle_b: Pubkey::new_unique(),
+        woopool_b: Pubkey::new_unique(),
+        feed_account_b: Pubkey::new_unique(),
+        price_update_b: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        token_vault_b: Pubkey::new_unique(),
+        quote_token_mint: Pubkey::new_unique(),
+        quote_feed_account: Pubkey::new_unique(),
+        quote_price_update: Pubkey::new_unique(),
+        quote_woopool: Pubkey::new_unique(),
+        quote_vault: Pubkey::new_unique(),
+    }
+}
+
+fn woofi_swap(pool: &WooAmmPool) -> WoofiSwap {
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+
+    let keyed_account = KeyedAccount {
+        key: Pubkey::new_unique(),
+        account: Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: woofi_jupiter::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+        params: None,
+    };
+    let amm_context = AmmContext {
+        clock_ref: ClockRef::from(Clock::default()),
+    };
+
+    WoofiSwap::from_keyed_account(&keyed_account, &amm_context).unwrap()
+}
+
+fn swap_params<'a>(
+    source_mint: Pubkey,
+    destination_mint: Pubkey,
+    token_transfer_authority: Pubkey,
+    jupiter_program_id: &'a Pubkey,
+) -> SwapParams<'a, 'a> {
+    SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: 1_000_000,
+        out_amount: 0,
+        source_mint,
+        destination_mint,
+        source_token_account: Pubkey::new_unique(),
+        destination_token_account: Pubkey::new_unique(),
+        token_transfer_authority,
+        quote_mint_to_referrer: None,
+        jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    }
+}
+
+#[test]
+fn test_swap_and_account_metas_a_to_b() {
+    let pool = woo_amm_pool();
+    let woofi_swap = woofi_swap(&pool);
+    let jupiter_program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let params = swap_params(
+        pool.token_mint_a,
+        pool.token_mint_b,
+        authority,
+        &jupiter_program_id,
+    );
+
+    let result = woofi_swap.get_swap_and_account_metas(&params).unwrap();
+
+    assert!(matches!(result.swap, Swap::Woofi));
+    assert_eq!(
+        result.account_metas,
+        vec![
+            AccountMeta::new_readonly(pool.wooconfig, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(pool.wooracle_a, false),
+            AccountMeta::new(pool.woopool_a, false),
+            AccountMeta::new(params.source_token_account, false),
+            AccountMeta::new(pool.token_vault_a, false),
+            AccountMeta::new_readonly(pool.price_update_a, false),
+            AccountMeta::new(pool.wooracle_b, false),
+            AccountMeta::new(pool.woopool_b, false),
+            AccountMeta::new(params.destination_token_account, false),
+            AccountMeta::new(pool.token_vault_b, false),
+            AccountMeta::new_readonly(pool.price_update_b, false),
+            AccountMeta::new(pool.quote_woopool, false),
+            AccountMeta::new_readonly(pool.quote_price_update, false),
+            AccountMeta::new(pool.quote_vault, false),
+            AccountMeta::new_readonly(authority, false),
+        ]
+    );
+}
+
+#[test]
+fn test_swap_and_account_metas_b_to_a() {
+    let pool = woo_amm_pool();
+    let woofi_swap = woofi_swap(&pool);
+    let jupiter_program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let params = swap_params(
+        pool.token_mint_b,
+        pool.token_mint_a,
+        authority,
+        &jupiter_program_id,
+    );
+
+    let result = woofi_swap.get_swap_and_account_metas(&params).unwrap();
+
+    assert!(matches!(result.swap, Swap::Woofi));
+    assert_eq!(
+        result.account_metas,
+        vec![
+            AccountMeta::new_readonly(pool.wooconfig, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(pool.wooracle_b, false),
+            AccountMeta::new(pool.woopool_b, false),
+            AccountMeta::new(params.source_token_account, false),
+            AccountMeta::new(pool.token_vault_b, false),
+            AccountMeta::new_readonly(pool.price_update_b, false),
+            AccountMeta::new(pool.wooracle_a, false),
+            AccountMeta::new(pool.woopool_a, false),
+            AccountMeta::new(params.destination_token_account, false),
+            AccountMeta::new(pool.token_vault_a, false),
+            AccountMeta::new_readonly(pool.price_update_a, false),
+            AccountMeta::new(pool.quote_woopool, false),
+            AccountMeta::new_readonly(pool.quote_price_update, false),
+            AccountMeta::new(pool.quote_vault, false),
+            AccountMeta::new_readonly(authority, false),
+        ]
+    );
+}
+
+#[test]
+fn test_swap_and_account_metas_rebate_to() {
+    let pool = woo_amm_pool();
+    let mut woofi_swap = woofi_swap(&pool);
+    let rebate_to = Pubkey::new_unique();
+    woofi_swap.rebate_to = Some(rebate_to);
+
+    let jupiter_program_id = Pubkey::new_unique();
+    let params = swap_params(
+        pool.token_mint_a,
+        pool.token_mint_b,
+        Pubkey::new_unique(),
+        &jupiter_program_id,
+    );
+
+    let result = woofi_swap.get_swap_and_account_metas(&params).unwrap();
+
+    assert_eq!(result.account_metas.len(), 17);
+    assert_eq!(
+        result.account_metas[16],
+        AccountMeta::new_readonly(rebate_to, false)
+    );
+}
+
+#[test]
+fn test_swap_and_account_metas_unknown_mint() {
+    let pool = woo_amm_pool();
+    let woofi_swap = woofi_swap(&pool);
+    let jupiter_program_id = Pubkey::new_unique();
+    let params = swap_params(
+        pool.token_mint_a,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        &jupiter_program_id,
+    );
+
+    assert!(woofi_swap.get_swap_and_account_metas(&params).is_err());
+}
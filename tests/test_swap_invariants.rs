@@ -0,0 +1,236 @@
+use jupiter_amm_interface::{Amm, SwapMode};
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+use woofi_jupiter::{
+    state::WooPool,
+    testing::{quote_params, MarketFixture, TokenSpec, WooPoolBuilder},
+    util::{
+        calc_base_amount_sell_quote, calc_quote_amount_sell_base, Decimals, GetStateResult, SOL,
+        USDC,
+    },
+    WoofiSwap,
+};
+
+// A base token priced between 0.01 and 10,000 quote with at least 4 decimals, so one more
+// unit of quote always buys at least one more unit of base before rounding
+#[derive(Clone, Copy, Debug)]
+struct PoolConfig {
+    base_decimals: u8,
+    price: u128,
+    coeff: u64,
+    spread: u64,
+}
+
+impl PoolConfig {
+    fn woopool(&self) -> WooPool {
+        let key = Pubkey::default();
+        WooPoolBuilder::new(key, key, key, key, key)
+            .base_decimals(self.base_decimals)
+            .build()
+    }
+
+    fn decimals(&self) -> Decimals {
+        Decimals::new(8, 6, self.base_decimals as u32).unwrap()
+    }
+
+    fn state(&self) -> GetStateResult {
+        GetStateResult {
+            price_out: self.price,
+            spread: self.spread,
+            coeff: self.coeff,
+            feasible_out: true,
+        }
+    }
+}
+
+fn pool_config() -> impl Strategy<Value = PoolConfig> {
+    (
+        4..=9_u8,
+        1_000_000..=1_000_000_000_000_u128,
+        0..=10_000_000_000_u64,
+        0..=10_000_000_000_000_000_u64,
+    )
+        .prop_map(|(base_decimals, price, coeff, spread)| PoolConfig {
+            base_decimals,
+            price,
+            coeff,
+            spread,
+        })
+}
+
+// A SOL/USDC market with randomized SOL decimals, oracle state, fee rate and reserves
+fn market(
+    decimals: impl Strategy<Value = u8>,
+    price: impl Strategy<Value = i64>,
+    coeff: impl Strategy<Value = u64>,
+    spread: impl Strategy<Value = u64>,
+) -> impl Strategy<Value = MarketFixture> {
+    (
+        decimals,
+        price,
+        coeff,
+        spread,
+        0..=1_000_u16,
+        any::<u64>(),
+        any::<u64>(),
+    )
+        .prop_map(
+            |(decimals, price, coeff, spread, fee_rate, sol_vault, usdc_vault)| {
+                let sol = TokenSpec {
+                    decimals,
+                    price,
+                    coeff,
+                    spread,
+                    vault_amount: sol_vault,
+                    ..TokenSpec::sol()
+                };
+                let usdc = TokenSpec {
+                    vault_amount: usdc_vault,
+                    ..TokenSpec::usdc()
+                };
+                let mut fixture =
+                    MarketFixture::new(woofi_jupiter::id(), sol, usdc, TokenSpec::usdc());
+                fixture.token_a.woopool.fee_rate = fee_rate;
+                fixture
+            },
+        )
+}
+
+fn valid_market() -> impl Strategy<Value = MarketFixture> {
+    market(
+        4..=9_u8,
+        1_000_000..=1_000_000_000_000_i64,
+        0..=10_000_000_000_u64,
+        0..=10_000_000_000_000_000_u64,
+    )
+}
+
+// every config the builders can express, including ones the program would reject
+fn any_market() -> impl Strategy<Value = MarketFixture> {
+    market(0..=18_u8, 1..=i64::MAX, any::<u64>(), any::<u64>())
+}
+
+// mostly amounts that stay within the pool limits, plus the full u64 range
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![1..=1_000_000_000_000_u64, any::<u64>()]
+}
+
+fn woofi_swap(fixture: &MarketFixture) -> WoofiSwap {
+    fixture.woofi_swap().unwrap()
+}
+
+proptest! {
+    #[test]
+    fn prop_sell_base_monotonic(config in pool_config(), x in amount(), y in amount()) {
+        let (woopool, decimals, state) = (config.woopool(), config.decimals(), config.state());
+        let (lo, hi) = (x.min(y) as u128, x.max(y) as u128);
+
+        let out_lo = calc_quote_amount_sell_base(lo, &woopool, &decimals, &state);
+        let out_hi = calc_quote_amount_sell_base(hi, &woopool, &decimals, &state);
+        if let (Ok((out_lo, _)), Ok((out_hi, _))) = (out_lo, out_hi) {
+            prop_assert!(out_lo <= out_hi, "{} -> {}, {} -> {}", lo, out_lo, hi, out_hi);
+        }
+    }
+
+    #[test]
+    fn prop_sell_quote_monotonic(config in pool_config(), x in amount(), y in amount()) {
+        let (woopool, decimals, state) = (config.woopool(), config.decimals(), config.state());
+        let (lo, hi) = (x.min(y) as u128, x.max(y) as u128);
+
+        let out_lo = calc_base_amount_sell_quote(lo, &woopool, &decimals, &state);
+        let out_hi = calc_base_amount_sell_quote(hi, &woopool, &decimals, &state);
+        if let (Ok((out_lo, _)), Ok((out_hi, _))) = (out_lo, out_hi) {
+            prop_assert!(out_lo <= out_hi, "{} -> {}, {} -> {}", lo, out_lo, hi, out_hi);
+        }
+    }
+
+    // selling base pushes the price down, selling quote pushes it up
+    #[test]
+    fn prop_new_price_direction(config in pool_config(), amount in amount()) {
+        let (woopool, decimals, state) = (config.woopool(), config.decimals(), config.state());
+
+        if let Ok((_, new_price)) = calc_quote_amount_sell_base(amount as u128, &woopool, &decimals, &state) {
+            prop_assert!(new_price <= config.price);
+        }
+        if let Ok((_, new_price)) = calc_base_amount_sell_quote(amount as u128, &woopool, &decimals, &state) {
+            prop_assert!(new_price >= config.price);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_quote_monotonic(fixture in valid_market(), x in amount(), y in amount(), sell_base in any::<bool>()) {
+        let woofi_swap = woofi_swap(&fixture);
+        let (input_mint, output_mint) = if sell_base { (SOL, USDC) } else { (USDC, SOL) };
+        let (lo, hi) = (x.min(y), x.max(y));
+
+        let out_lo = woofi_swap.quote(&quote_params(lo, input_mint, output_mint, SwapMode::ExactIn));
+        let out_hi = woofi_swap.quote(&quote_params(hi, input_mint, output_mint, SwapMode::ExactIn));
+        if let (Ok(out_lo), Ok(out_hi)) = (out_lo, out_hi) {
+            prop_assert!(out_lo.out_amount <= out_hi.out_amount);
+        }
+    }
+
+    #[test]
+    fn prop_round_trip_unprofitable(fixture in valid_market(), amount in amount(), sell_base in any::<bool>()) {
+        let woofi_swap = woofi_swap(&fixture);
+        let (input_mint, output_mint) = if sell_base { (SOL, USDC) } else { (USDC, SOL) };
+
+        let Ok(forward) = woofi_swap.quote(&quote_params(amount, input_mint, output_mint, SwapMode::ExactIn)) else {
+            return Ok(());
+        };
+        let back = woofi_swap.quote(&quote_params(forward.out_amount, output_mint, input_mint, SwapMode::ExactIn));
+        if let Ok(back) = back {
+            prop_assert!(back.out_amount <= amount, "{} -> {} -> {}", amount, forward.out_amount, back.out_amount);
+        }
+    }
+
+    // the fee is taken in quote and never exceeds the input's value at the oracle price
+    #[test]
+    fn prop_fee_within_notional(fixture in valid_market(), amount in amount(), sell_base in any::<bool>()) {
+        let woofi_swap = woofi_swap(&fixture);
+        let (input_mint, output_mint) = if sell_base { (SOL, USDC) } else { (USDC, SOL) };
+
+        if let Ok(quote) = woofi_swap.quote(&quote_params(amount, input_mint, output_mint, SwapMode::ExactIn)) {
+            let wooracle = &fixture.token_a.wooracle;
+            let notional = if sell_base {
+                amount as u128 * wooracle.price * 10_u128.pow(wooracle.quote_decimals as u32)
+                    / 10_u128.pow(wooracle.price_decimals as u32)
+                    / 10_u128.pow(wooracle.base_decimals as u32)
+            } else {
+                amount as u128
+            };
+            prop_assert!(quote.fee_amount as u128 <= notional);
+        }
+    }
+
+    #[test]
+    fn prop_swap_new_price_direction(fixture in valid_market(), amount in amount()) {
+        let woofi_swap = woofi_swap(&fixture);
+        let price = fixture.token_a.wooracle.price;
+
+        if let Ok(result) = woofi_swap.compute_swap(amount as u128, &SOL, &USDC) {
+            prop_assert!(result.new_price_from.unwrap() <= price);
+        }
+        if let Ok(result) = woofi_swap.compute_swap(amount as u128, &USDC, &SOL) {
+            prop_assert!(result.new_price_to.unwrap() >= price);
+        }
+    }
+
+    // results are unchecked here, only that every path returns
+    #[test]
+    fn prop_quote_never_panics(fixture in any_market(), amount in any::<u64>()) {
+        let Ok(woofi_swap) = fixture.woofi_swap() else {
+            return Ok(());
+        };
+
+        for (input_mint, output_mint) in [(SOL, USDC), (USDC, SOL)] {
+            for swap_mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+                let _ = woofi_swap.quote(&quote_params(amount, input_mint, output_mint, swap_mode));
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use anchor_lang::prelude::Pubkey;
+use woofi_jupiter::{
+    errors::QuoteError,
+    state::WooPool,
+    util::{
+        calc_base_amount_buy_quote, calc_base_amount_sell_quote, calc_quote_amount_buy_base,
+        calc_quote_amount_sell_base, Decimals, GetStateResult,
+    },
+};
+
+// SOL/USDC-like pool: price 150 with 8 price decimals, 6 quote decimals, 9 base decimals
+fn sol_woopool() -> WooPool {
+    WooPool {
+        wooconfig: Pubkey::default(),
+        woopool_bump: [0],
+        authority: Pubkey::default(),
+        wooracle: Pubkey::default(),
+        fee_rate: 25,
+        max_gamma: 10_000_000_000_000_000,
+        max_notional_swap: 1_000_000_000_000,
+        cap_bal: u128::MAX,
+        min_swap_amount: 0,
+        unclaimed_fee: 0,
+        token_mint: Pubkey::default(),
+        token_vault: Pubkey::default(),
+        quote_token_mint: Pubkey::default(),
+        base_decimals: 9,
+    }
+}
+
+fn sol_decimals() -> Decimals {
+    Decimals::new(8, 6, 9).unwrap()
+}
+
+fn sol_state() -> GetStateResult {
+    GetStateResult {
+        price_out: 15_000_000_000,
+        spread: 100_000_000_000_000,
+        coeff: 1_000_000_000,
+        feasible_out: true,
+    }
+}
+
+#[test]
+fn test_calc_base_amount_buy_quote_is_minimal() {
+    let (woopool, decimals, state) = (sol_woopool(), sol_decimals(), sol_state());
+
+    for quote_amount in [1_u128, 999, 1_000_000, 150_000_000, 123_456_789_012] {
+        let base_amount =
+            calc_base_amount_buy_quote(quote_amount, &woopool, &decimals, &state).unwrap();
+
+        let (out, _) =
+            calc_quote_amount_sell_base(base_amount, &woopool, &decimals, &state).unwrap();
+        assert!(out >= quote_amount);
+
+        let (out_below, _) =
+            calc_quote_amount_sell_base(base_amount - 1, &woopool, &decimals, &state).unwrap();
+        assert!(out_below < quote_amount);
+    }
+}
+
+#[test]
+fn test_calc_quote_amount_buy_base_is_minimal() {
+    let (woopool, decimals, state) = (sol_woopool(), sol_decimals(), sol_state());
+
+    for base_amount in [1_u128, 1_000, 10_000_000, 1_000_000_000, 500_000_000_000] {
+        let quote_amount =
+            calc_quote_amount_buy_base(base_amount, &woopool, &decimals, &state).unwrap();
+
+        let (out, _) =
+            calc_base_amount_sell_quote(quote_amount, &woopool, &decimals, &state).unwrap();
+        assert!(out >= base_amount);
+
+        let (out_below, _) =
+            calc_base_amount_sell_quote(quote_amount - 1, &woopool, &decimals, &state).unwrap();
+        assert!(out_below < base_amount);
+    }
+}
+
+#[test]
+fn test_exact_out_zero_amount() {
+    let (woopool, decimals, state) = (sol_woopool(), sol_decimals(), sol_state());
+
+    assert_eq!(
+        calc_base_amount_buy_quote(0, &woopool, &decimals, &state).unwrap(),
+        0
+    );
+    assert_eq!(
+        calc_quote_amount_buy_base(0, &woopool, &decimals, &state).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_exact_out_beyond_max_notional() {
+    let (woopool, decimals, state) = (sol_woopool(), sol_decimals(), sol_state());
+
+    // more than max_notional_swap can ever produce
+    for error in [
+        calc_base_amount_buy_quote(2_000_000_000_000, &woopool, &decimals, &state).unwrap_err(),
+        calc_quote_amount_buy_base(10_000_000_000_000, &woopool, &decimals, &state).unwrap_err(),
+    ] {
+        assert!(matches!(
+            error.downcast_ref::<QuoteError>(),
+            Some(QuoteError::MaxNotionalExceeded { .. })
+        ));
+    }
+}
+
+// max_gamma up to 1e18 and no notional cap, so the output peaks and then falls without an error
+fn high_gamma_woopool() -> WooPool {
+    WooPool {
+        max_gamma: 1_000_000_000_000_000_000,
+        max_notional_swap: u128::MAX,
+        ..sol_woopool()
+    }
+}
+
+#[test]
+fn test_exact_out_high_max_gamma() {
+    let (woopool, decimals, state) = (high_gamma_woopool(), sol_decimals(), sol_state());
+
+    // gamma = 150 * base_amount and 1_000 * quote_amount, so the outputs peak where
+    // 2 * gamma + spread = 1e18
+    let peak_base: u128 = (1_000_000_000_000_000_000 - 100_000_000_000_000) / 300;
+    let peak_quote: u128 = (1_000_000_000_000_000_000 - 100_000_000_000_000) / 2_000;
+    let (peak_quote_out, _) =
+        calc_quote_amount_sell_base(peak_base, &woopool, &decimals, &state).unwrap();
+    let (peak_base_out, _) =
+        calc_base_amount_sell_quote(peak_quote, &woopool, &decimals, &state).unwrap();
+
+    // twice the peak input still quotes, for less
+    let (out, _) =
+        calc_quote_amount_sell_base(2 * peak_base - 1, &woopool, &decimals, &state).unwrap();
+    assert!(out < peak_quote_out);
+    let (out, _) =
+        calc_base_amount_sell_quote(2 * peak_quote - 1, &woopool, &decimals, &state).unwrap();
+    assert!(out < peak_base_out);
+
+    // up to the peak the smallest input is found on the rising side
+    for quote_amount in [peak_quote_out / 2, peak_quote_out - 1_000, peak_quote_out] {
+        let base_amount =
+            calc_base_amount_buy_quote(quote_amount, &woopool, &decimals, &state).unwrap();
+        assert!(base_amount <= peak_base);
+
+        let (out, _) =
+            calc_quote_amount_sell_base(base_amount, &woopool, &decimals, &state).unwrap();
+        assert!(out >= quote_amount);
+        let (out_below, _) =
+            calc_quote_amount_sell_base(base_amount - 1, &woopool, &decimals, &state).unwrap();
+        assert!(out_below < quote_amount);
+    }
+    for base_amount in [peak_base_out / 2, peak_base_out - 1_000, peak_base_out] {
+        let quote_amount =
+            calc_quote_amount_buy_base(base_amount, &woopool, &decimals, &state).unwrap();
+        assert!(quote_amount <= peak_quote);
+
+        let (out, _) =
+            calc_base_amount_sell_quote(quote_amount, &woopool, &decimals, &state).unwrap();
+        assert!(out >= base_amount);
+        let (out_below, _) =
+            calc_base_amount_sell_quote(quote_amount - 1, &woopool, &decimals, &state).unwrap();
+        assert!(out_below < base_amount);
+    }
+
+    // past the peak no input reaches the target
+    let error =
+        calc_base_amount_buy_quote(peak_quote_out + 1, &woopool, &decimals, &state).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<QuoteError>(),
+        Some(&QuoteError::OutputUnreachable {
+            target: peak_quote_out + 1,
+            max_out: peak_quote_out,
+        })
+    );
+    let error =
+        calc_quote_amount_buy_base(peak_base_out + 1, &woopool, &decimals, &state).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<QuoteError>(),
+        Some(&QuoteError::OutputUnreachable {
+            target: peak_base_out + 1,
+            max_out: peak_base_out,
+        })
+    );
+}
+
+#[test]
+fn test_exact_out_returns_max_gamma_error() {
+    // max_gamma binds before the peak, so the target past it returns GammaExceeded
+    let woopool = WooPool {
+        max_gamma: 100_000_000_000_000_000,
+        ..high_gamma_woopool()
+    };
+    let (decimals, state) = (sol_decimals(), sol_state());
+
+    let (max_quote_out, _) =
+        calc_quote_amount_sell_base(100_000_000_000_000_000 / 150, &woopool, &decimals, &state)
+            .unwrap();
+    let error =
+        calc_base_amount_buy_quote(max_quote_out + 1, &woopool, &decimals, &state).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<QuoteError>(),
+        Some(QuoteError::GammaExceeded { .. })
+    ));
+}
+
+#[test]
+fn test_exact_out_not_feasible() {
+    let (woopool, decimals) = (sol_woopool(), sol_decimals());
+    let state = GetStateResult {
+        feasible_out: false,
+        ..sol_state()
+    };
+
+    assert!(calc_base_amount_buy_quote(1_000_000, &woopool, &decimals, &state).is_err());
+    assert!(calc_quote_amount_buy_base(1_000_000, &woopool, &decimals, &state).is_err());
+}
@@ -0,0 +1,150 @@
+use std::{collections::HashSet, sync::atomic::Ordering};
+
+use jupiter_amm_interface::{AccountMap, Amm, SwapMode};
+use solana_sdk::{pubkey::Pubkey, sysvar};
+use woofi_jupiter::{
+    testing::{quote_params, sell_sol, MarketFixture, FIXTURE_WOO},
+    util::SOL,
+    WoofiSwap,
+};
+
+#[test]
+fn test_update_refreshes_clock() {
+    let mut fixture = MarketFixture::sol_usdc();
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+    assert!(woofi_swap.quote(&sell_sol()).is_ok());
+
+    // past the wooracle stale_duration (120s) while Pyth keeps publishing
+    fixture.advance_clock(200);
+    fixture.refresh_price_updates();
+    woofi_swap.update(&fixture.account_map()).unwrap();
+
+    assert_eq!(
+        woofi_swap.clock_ref.unix_timestamp.load(Ordering::Relaxed),
+        fixture.clock.unix_timestamp
+    );
+    assert_eq!(
+        woofi_swap.clock_ref.slot.load(Ordering::Relaxed),
+        fixture.clock.slot
+    );
+    assert_eq!(
+        woofi_swap.clock_ref.epoch.load(Ordering::Relaxed),
+        fixture.clock.epoch
+    );
+
+    assert!(!woofi_swap.state_a.unwrap().feasible_out);
+    assert!(woofi_swap.quote(&sell_sol()).is_err());
+}
+
+#[test]
+fn test_update_clock_is_per_market() {
+    let mut fixture = MarketFixture::sol_usdc();
+    let amm_context = fixture.amm_context();
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &amm_context).unwrap();
+    let other_swap = WoofiSwap::from_keyed_account(&fixture.keyed_account(), &amm_context).unwrap();
+
+    fixture.advance_clock(200);
+    fixture.refresh_price_updates();
+    woofi_swap.update(&fixture.account_map()).unwrap();
+
+    // the snapshot clock stays with the market that read it
+    assert_eq!(
+        woofi_swap.clock_ref.unix_timestamp.load(Ordering::Relaxed),
+        fixture.clock.unix_timestamp
+    );
+    assert_eq!(
+        woofi_swap.clock_ref.slot.load(Ordering::Relaxed),
+        fixture.clock.slot
+    );
+    assert_eq!(
+        amm_context.clock_ref.unix_timestamp.load(Ordering::Relaxed),
+        fixture.clock.unix_timestamp - 200
+    );
+    assert_eq!(
+        other_swap.clock_ref.unix_timestamp.load(Ordering::Relaxed),
+        fixture.clock.unix_timestamp - 200
+    );
+}
+
+#[test]
+fn test_update_keeps_clock_without_sysvar() {
+    let fixture = MarketFixture::sol_usdc();
+    let mut woofi_swap = fixture.woofi_swap().unwrap();
+
+    let mut account_map = fixture.account_map();
+    account_map.remove(&sysvar::clock::ID);
+    woofi_swap.update(&account_map).unwrap();
+
+    assert_eq!(
+        woofi_swap.clock_ref.unix_timestamp.load(Ordering::Relaxed),
+        fixture.clock.unix_timestamp
+    );
+    assert!(woofi_swap.state_a.unwrap().feasible_out);
+}
+
+// only the accounts the market asks for
+fn requested_account_map(fixture: &MarketFixture, woofi_swap: &WoofiSwap) -> AccountMap {
+    let account_map = fixture.account_map();
+    woofi_swap
+        .get_accounts_to_update()
+        .into_iter()
+        .map(|key| (key, account_map[&key].clone()))
+        .collect()
+}
+
+#[test]
+fn test_accounts_to_update_deduplicated() {
+    let fixture = MarketFixture::sol_usdc();
+    let woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+
+    let accounts = woofi_swap.get_accounts_to_update();
+    let unique: HashSet<Pubkey> = accounts.iter().copied().collect();
+    assert_eq!(unique.len(), accounts.len());
+
+    // USDC is token b, so its woopool, price update and vault are only listed once
+    assert_eq!(accounts.len(), 10);
+    assert!(accounts.contains(&fixture.quote.woopool_address));
+    assert!(accounts.contains(&fixture.quote.price_update_address));
+}
+
+#[test]
+fn test_update_with_requested_accounts_only() {
+    for fixture in [MarketFixture::sol_usdc(), MarketFixture::sol_woo()] {
+        let mut woofi_swap =
+            WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context())
+                .unwrap();
+        woofi_swap
+            .update(&requested_account_map(&fixture, &woofi_swap))
+            .unwrap();
+
+        assert!(woofi_swap.quote(&sell_sol()).is_ok());
+    }
+}
+
+#[test]
+fn test_accounts_to_update_base_to_base() {
+    let fixture = MarketFixture::sol_woo();
+    let mut woofi_swap =
+        WoofiSwap::from_keyed_account(&fixture.keyed_account(), &fixture.amm_context()).unwrap();
+
+    let accounts = woofi_swap.get_accounts_to_update();
+    assert_eq!(accounts.len(), 13);
+    assert!(accounts.contains(&fixture.quote.woopool_address));
+    assert!(accounts.contains(&fixture.quote.price_update_address));
+    assert!(accounts.contains(&fixture.quote.vault_address));
+
+    woofi_swap
+        .update(&requested_account_map(&fixture, &woofi_swap))
+        .unwrap();
+    let result = woofi_swap
+        .quote(&quote_params(
+            1_000_000_000,
+            SOL,
+            FIXTURE_WOO,
+            SwapMode::ExactIn,
+        ))
+        .unwrap();
+    assert!(result.out_amount > 0);
+}